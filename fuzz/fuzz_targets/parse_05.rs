@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../src/bin/05.rs"]
+mod day;
+
+fuzz_target!(|input: &str| {
+    let _ = day::parse(input);
+});
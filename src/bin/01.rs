@@ -1,22 +1,32 @@
-advent_of_code::solution!(1);
+use advent_of_code::parse::{parse_number, ParseError};
 
-pub fn parse(input: &str) -> Vec<Vec<u32>> {
-    let location_ids = input
+advent_of_code::solution!(1, parse = parse, generate = generate);
+
+pub fn parse(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    input
         .split('\n')
-        .filter(|location_list| !location_list.is_empty())
-        .map(|location_list| {
-            location_list
+        .enumerate()
+        .filter(|(_, location_list)| !location_list.trim().is_empty())
+        .map(|(line_index, location_list)| {
+            let location_ids = location_list
                 .split_ascii_whitespace()
-                .map(|location_str| location_str.parse::<u32>().unwrap())
-                .collect()
-        })
-        .collect();
+                .map(|location_str| parse_number(location_str, line_index + 1))
+                .collect::<Result<Vec<u32>, _>>()?;
 
-    location_ids
+            if location_ids.len() != 2 {
+                return Err(ParseError::new(
+                    line_index + 1,
+                    "expected exactly two location ids",
+                ));
+            }
+
+            Ok(location_ids)
+        })
+        .collect()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let location_ids = parse(input);
+    let location_ids = parse(input).ok()?;
     let mut left_list: Vec<&u32> = location_ids
         .iter()
         .map(|location_list| location_list.first().unwrap())
@@ -31,14 +41,14 @@ pub fn part_one(input: &str) -> Option<u32> {
     let total_differences: u32 = left_list
         .iter()
         .zip(right_list.iter())
-        .map(|(left, right)| (**left as i32 - **right as i32).unsigned_abs())
+        .map(|(left, right)| left.abs_diff(**right))
         .sum();
 
     Some(total_differences)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let location_ids = parse(input);
+    let location_ids = parse(input).ok()?;
     let left_list: Vec<&u32> = location_ids
         .iter()
         .map(|location_list| location_list.first().unwrap())
@@ -56,9 +66,28 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(similarity_score)
 }
 
+/// Generates `size` pairs of five digit location ids, with some ids repeated across both lists.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let pool: Vec<u32> = (0..size.max(1)).map(|_| rng.u32(10_000..100_000)).collect();
+
+    (0..size)
+        .map(|_| {
+            let left = rng.choice(&pool).copied().unwrap_or(0);
+            let right = if rng.bool() {
+                rng.choice(&pool).copied().unwrap_or(0)
+            } else {
+                rng.u32(10_000..100_000)
+            };
+            format!("{left}   {right}\n")
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -71,4 +100,42 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(31));
     }
+
+    fn location_lists() -> impl Strategy<Value = Vec<(u32, u32)>> {
+        prop::collection::vec((0..100_000_u32, 0..100_000_u32), 0..100)
+    }
+
+    fn to_input(locations: &[(u32, u32)]) -> String {
+        locations
+            .iter()
+            .map(|(left, right)| format!("{left}   {right}\n"))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9 \n\t-]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(locations in location_lists()) {
+            let parsed = parse(&to_input(&locations)).unwrap();
+            let expected: Vec<Vec<u32>> = locations.iter().map(|(l, r)| vec![*l, *r]).collect();
+            prop_assert_eq!(parsed, expected);
+        }
+
+        #[test]
+        fn test_part_two_matches_reference(locations in location_lists()) {
+            let mut counts = std::collections::HashMap::new();
+            for (_, right) in &locations {
+                *counts.entry(*right).or_insert(0) += 1;
+            }
+            let expected: u32 = locations
+                .iter()
+                .map(|(left, _)| left * counts.get(left).unwrap_or(&0))
+                .sum();
+            prop_assert_eq!(part_two(&to_input(&locations)), Some(expected));
+        }
+    }
 }
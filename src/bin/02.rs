@@ -1,18 +1,25 @@
-advent_of_code::solution!(2);
+use advent_of_code::parse::{parse_number, ParseError};
+use std::ops::RangeInclusive;
 
-pub fn parse(input: &str) -> Vec<Vec<i32>> {
-    let reports = input
+advent_of_code::solution!(
+    2,
+    parse = parse,
+    reference_two = reference_part_two,
+    generate = generate
+);
+
+pub fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    input
         .split('\n')
-        .filter(|report| !report.is_empty())
-        .map(|report| {
+        .enumerate()
+        .filter(|(_, report)| !report.trim().is_empty())
+        .map(|(line_index, report)| {
             report
                 .split_ascii_whitespace()
-                .map(|level| level.parse::<i32>().unwrap())
+                .map(|level| parse_number(level, line_index + 1))
                 .collect()
         })
-        .collect();
-
-    reports
+        .collect()
 }
 
 pub fn is_safe(report: &[i32]) -> bool {
@@ -29,45 +36,163 @@ pub fn is_safe(report: &[i32]) -> bool {
     (only_decreasing ^ only_increasing) && distance_ok
 }
 
-pub fn is_safe_with_removal(report: &[i32]) -> bool {
-    if is_safe(report) {
-        return true;
-    }
+/// How safe a report has to be: the allowed differences between adjacent levels in the
+/// direction of the report, and how many levels may be removed to get there.
+#[derive(Clone, Debug)]
+pub struct Tolerance {
+    pub steps: RangeInclusive<i32>,
+    pub removals: usize,
+}
+
+pub const PART_ONE: Tolerance = Tolerance {
+    steps: 1..=3,
+    removals: 0,
+};
+
+pub const PART_TWO: Tolerance = Tolerance {
+    steps: 1..=3,
+    removals: 1,
+};
 
-    for level_index in 0..report.len() {
-        let mut report_clone = report.to_vec();
-        report_clone.remove(level_index);
+/// The fewest levels to remove, as indices, so that the report is safe under `tolerance`:
+/// empty if it is safe as is, [`None`] if more than `tolerance.removals` levels would have to go.
+/// Like [`is_safe`], at least two levels have to remain to tell the direction of the report.
+pub fn levels_to_remove(report: &[i32], tolerance: &Tolerance) -> Option<Vec<usize>> {
+    [1, -1]
+        .into_iter()
+        .filter_map(|direction| levels_to_remove_in_direction(report, tolerance, direction))
+        .min_by_key(Vec::len)
+}
+
+/// Finds the kept levels with a dynamic program over `(level, removed so far)`: a kept level can
+/// follow one of the `removals + 1` levels before it, so this runs in O(n * k²).
+fn levels_to_remove_in_direction(
+    report: &[i32],
+    tolerance: &Tolerance,
+    direction: i32,
+) -> Option<Vec<usize>> {
+    let (len, max_removals) = (report.len(), tolerance.removals);
+
+    // `previous[index][removed]` is set if the level at `index` can be kept with `removed` levels
+    // removed before it, holding the kept level before it, if any.
+    let mut previous: Vec<Vec<Option<Option<usize>>>> = vec![vec![None; max_removals + 1]; len];
+    for (first, removed) in previous.iter_mut().take(max_removals + 1).enumerate() {
+        removed[first] = Some(None);
+    }
 
-        if is_safe(&report_clone) {
-            return true;
+    for index in 0..len {
+        for removed in 0..=max_removals {
+            if previous[index][removed].is_none() {
+                continue;
+            }
+            for next in index + 1..len {
+                let removed = removed + (next - index - 1);
+                if removed > max_removals {
+                    break;
+                }
+                let step = (report[next] - report[index]) * direction;
+                if tolerance.steps.contains(&step) && previous[next][removed].is_none() {
+                    previous[next][removed] = Some(Some(index));
+                }
+            }
         }
     }
 
-    false
+    // the levels after the last kept one are removed as well.
+    let (last, removed) = (0..len)
+        .flat_map(|last| (0..=max_removals).map(move |removed| (last, removed)))
+        .filter(|&(last, removed)| {
+            matches!(previous[last][removed], Some(Some(_)))
+                && removed + len - 1 - last <= max_removals
+        })
+        .min_by_key(|&(last, removed)| removed + len - 1 - last)?;
+
+    let mut kept = vec![false; len];
+    let (mut index, mut removed) = (last, removed);
+    kept[index] = true;
+    while let Some(Some(before)) = previous[index][removed] {
+        removed -= index - before - 1;
+        index = before;
+        kept[index] = true;
+    }
+
+    Some((0..len).filter(|&index| !kept[index]).collect())
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let reports = parse(input);
+    let reports = parse(input).ok()?;
 
-    let safe_report_count = reports.iter().filter(|report| is_safe(report)).count();
+    let safe_report_count = reports
+        .iter()
+        .filter(|report| levels_to_remove(report, &PART_ONE).is_some())
+        .count();
 
     Some(safe_report_count)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let reports = parse(input);
+    let reports = parse(input).ok()?;
 
     let safe_report_count = reports
         .iter()
-        .filter(|report| is_safe_with_removal(report))
+        .filter(|report| levels_to_remove(report, &PART_TWO).is_some())
         .count();
 
     Some(safe_report_count)
 }
 
+/// Tries every single removal without any early exit.
+pub fn reference_is_safe_with_removal(report: &[i32]) -> bool {
+    (0..=report.len()).any(|skip| {
+        let levels: Vec<i32> = report
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != skip)
+            .map(|(_, level)| *level)
+            .collect();
+        is_safe(&levels)
+    })
+}
+
+/// Counts safe reports with [`reference_is_safe_with_removal`], used to cross-check [`part_two`].
+pub fn reference_part_two(input: &str) -> Option<usize> {
+    let reports = parse(input).ok()?;
+
+    Some(
+        reports
+            .iter()
+            .filter(|report| reference_is_safe_with_removal(report))
+            .count(),
+    )
+}
+
+/// Generates `size` reports that are mostly monotone with steps of 1 to 3 and some noise.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+
+    (0..size)
+        .map(|_| {
+            let direction = if rng.bool() { 1 } else { -1 };
+            let mut level = rng.i32(10..90);
+            let mut report = vec![level];
+            for _ in 0..rng.usize(4..8) {
+                level += direction * rng.i32(1..=3);
+                report.push(level);
+            }
+            for _ in 0..rng.usize(0..3) {
+                let index = rng.usize(..report.len());
+                report[index] = rng.i32(0..100);
+            }
+            let levels: Vec<String> = report.iter().map(i32::to_string).collect();
+            levels.join(" ") + "\n"
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -80,4 +205,118 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(4));
     }
+
+    #[test]
+    fn test_levels_to_remove() {
+        assert_eq!(levels_to_remove(&[7, 6, 4, 2, 1], &PART_TWO), Some(vec![]));
+        assert_eq!(levels_to_remove(&[1, 7, 2, 3], &PART_TWO), Some(vec![1]));
+        assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], &PART_TWO), None);
+
+        let two_removals = Tolerance {
+            removals: 2,
+            ..PART_TWO
+        };
+        assert_eq!(
+            levels_to_remove(&[1, 2, 7, 8, 9], &two_removals),
+            Some(vec![0, 1])
+        );
+
+        let wide_steps = Tolerance {
+            steps: 4..=4,
+            removals: 0,
+        };
+        assert_eq!(levels_to_remove(&[9, 5, 1], &wide_steps), Some(vec![]));
+        assert_eq!(levels_to_remove(&[9, 6, 3], &wide_steps), None);
+    }
+
+    /// Finds the fewest removals by trying every subset of levels, smallest first.
+    fn reference_fewest_removals(report: &[i32], max_removals: usize) -> Option<usize> {
+        (0..1_u32 << report.len())
+            .filter(|removed| removed.count_ones() as usize <= max_removals)
+            .filter(|removed| {
+                let levels: Vec<i32> = (0..report.len())
+                    .filter(|index| removed & (1 << index) == 0)
+                    .map(|index| report[index])
+                    .collect();
+                is_safe(&levels)
+            })
+            .map(|removed| removed.count_ones() as usize)
+            .min()
+    }
+
+    /// Generates strictly monotone reports with steps of 1 to 3, with an occasional level
+    /// replaced by noise so that both safe and unsafe reports are produced.
+    fn reports() -> impl Strategy<Value = Vec<Vec<i32>>> {
+        let report = (
+            0..100_i32,
+            any::<bool>(),
+            prop::collection::vec(1..=3_i32, 0..8),
+            prop::option::of((any::<prop::sample::Index>(), 0..100_i32)),
+        )
+            .prop_map(|(start, increasing, steps, noise)| {
+                let mut level = start;
+                let mut report = vec![level];
+                for step in steps {
+                    level += if increasing { step } else { -step };
+                    report.push(level);
+                }
+                if let Some((index, value)) = noise {
+                    let index = index.index(report.len());
+                    report[index] = value;
+                }
+                report
+            });
+        prop::collection::vec(report, 0..20)
+    }
+
+    fn to_input(reports: &[Vec<i32>]) -> String {
+        reports
+            .iter()
+            .map(|report| {
+                let levels: Vec<String> = report.iter().map(i32::to_string).collect();
+                format!("{}\n", levels.join(" "))
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9 \n-]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(reports in reports()) {
+            prop_assert_eq!(parse(&to_input(&reports)).unwrap(), reports);
+        }
+
+        #[test]
+        fn test_removal_matches_reference(reports in reports()) {
+            for report in &reports {
+                prop_assert_eq!(
+                    levels_to_remove(report, &PART_TWO).is_some(),
+                    reference_is_safe_with_removal(report)
+                );
+            }
+        }
+
+        #[test]
+        fn test_removals_match_reference(reports in reports(), max_removals in 0..4_usize) {
+            let tolerance = Tolerance { removals: max_removals, ..PART_ONE };
+            for report in &reports {
+                let removed = levels_to_remove(report, &tolerance);
+                prop_assert_eq!(
+                    removed.as_ref().map(Vec::len),
+                    reference_fewest_removals(report, max_removals)
+                );
+                if let Some(removed) = removed {
+                    let levels: Vec<i32> = (0..report.len())
+                        .filter(|index| !removed.contains(index))
+                        .map(|index| report[index])
+                        .collect();
+                    prop_assert!(is_safe(&levels));
+                }
+            }
+        }
+    }
 }
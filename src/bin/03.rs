@@ -1,98 +1,226 @@
-use regex::Regex;
+use std::fmt;
 
-advent_of_code::solution!(3);
+advent_of_code::solution!(3, parse = parse, generate = generate);
 
+/// The state of the program running in corrupted memory, which instructions act on.
 #[derive(Debug)]
-pub struct Instruction {
+pub struct Machine {
     pub enabled: bool,
-    pub first: u32,
-    pub second: u32,
 }
 
-impl Instruction {
-    pub fn new(enabled: bool, first: u32, second: u32) -> Self {
-        Self {
-            enabled,
-            first,
-            second,
+impl Default for Machine {
+    fn default() -> Self {
+        Machine { enabled: true }
+    }
+}
+
+/// An instruction the tokenizer recognises as `name(arg,...)`, with one to three digits for each
+/// of its `arity` arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct Definition {
+    pub name: &'static str,
+    pub arity: usize,
+    /// Runs the instruction, returning the value it adds to the result, if any.
+    pub execute: fn(&mut Machine, &[u32]) -> Option<u32>,
+}
+
+pub const MUL: Definition = Definition {
+    name: "mul",
+    arity: 2,
+    execute: |machine, args| machine.enabled.then(|| args[0] * args[1]),
+};
+
+pub const DO: Definition = Definition {
+    name: "do",
+    arity: 0,
+    execute: |machine, _| {
+        machine.enabled = true;
+        None
+    },
+};
+
+pub const DONT: Definition = Definition {
+    name: "don't",
+    arity: 0,
+    execute: |machine, _| {
+        machine.enabled = false;
+        None
+    },
+};
+
+/// Every instruction known to the interpreter. Add new ones here.
+pub const INSTRUCTIONS: &[Definition] = &[MUL, DO, DONT];
+
+/// An instruction found in memory, starting at byte `offset`.
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub offset: usize,
+    pub definition: Definition,
+    pub args: Vec<u32>,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args: Vec<String> = self.args.iter().map(u32::to_string).collect();
+        write!(f, "{}({})", self.definition.name, args.join(","))
+    }
+}
+
+/// An instruction that was executed, with the value it added to the result.
+#[derive(Clone, Debug)]
+pub struct Executed {
+    pub token: Token,
+    pub value: Option<u32>,
+}
+
+impl fmt::Display for Executed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6}: {}", self.token.offset, self.token)?;
+        match self.value {
+            Some(value) => write!(f, " = {value}"),
+            None => Ok(()),
         }
     }
+}
+
+/// Splits corrupted memory into the instructions of `table`, skipping everything else. Lines
+/// are not separated, the memory is one stream of bytes.
+pub fn tokenize(memory: &str, table: &[Definition]) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut offset = 0;
 
-    pub fn product(&self, part_one: bool) -> u32 {
-        if self.enabled || part_one {
-            self.first * self.second
-        } else {
-            0
+    while let Some(c) = memory[offset..].chars().next() {
+        let token = table.iter().find_map(|definition| {
+            let (args, end) = match_instruction(memory, offset, definition)?;
+            Some((
+                Token {
+                    offset,
+                    definition: *definition,
+                    args,
+                },
+                end,
+            ))
+        });
+
+        match token {
+            Some((token, end)) => {
+                tokens.push(token);
+                offset = end;
+            }
+            None => offset += c.len_utf8(),
         }
     }
+
+    tokens
 }
 
-pub fn parse(input: &str) -> Vec<Vec<Instruction>> {
-    let re = Regex::new(r"(?:(?:(?:do\(\)|don't\(\)).*?)?mul\((\d{1,3}),(\d{1,3})\))").unwrap();
-    let mut enabled = true;
-    let memory_lines = input
-        .split('\n')
-        .filter(|memory| !memory.is_empty())
-        .map(|memory| {
-            re.captures_iter(memory)
-                .map(|capture| {
-                    // Need to update this so that `enabled` propogates until a new do() or don't() is found
-                    let full_match = capture.get(0).unwrap().as_str();
-                    enabled = if full_match.contains("do()") {
-                        true
-                    } else if full_match.contains("don't()") {
-                        false
-                    } else {
-                        enabled
-                    };
-                    Instruction::new(
-                        enabled,
-                        capture.get(1).unwrap().as_str().parse::<u32>().unwrap(),
-                        capture.get(2).unwrap().as_str().parse::<u32>().unwrap(),
-                    )
-                })
-                .collect::<Vec<Instruction>>()
-        })
-        .collect();
+/// Matches `definition` at `offset`, returning its arguments and the offset right after it.
+fn match_instruction(
+    memory: &str,
+    offset: usize,
+    definition: &Definition,
+) -> Option<(Vec<u32>, usize)> {
+    let mut rest = memory[offset..]
+        .strip_prefix(definition.name)?
+        .strip_prefix('(')?;
+    let mut args = Vec::with_capacity(definition.arity);
+
+    for index in 0..definition.arity {
+        if index > 0 {
+            rest = rest.strip_prefix(',')?;
+        }
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if !(1..=3).contains(&digits) {
+            return None;
+        }
+        args.push(rest[..digits].parse().ok()?);
+        rest = &rest[digits..];
+    }
 
-    memory_lines
+    let rest = rest.strip_prefix(')')?;
+    Some((args, memory.len() - rest.len()))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let memory_lines = parse(input);
+/// Executes the instructions in order on a fresh [`Machine`].
+pub fn run(tokens: Vec<Token>) -> Vec<Executed> {
+    let mut machine = Machine::default();
 
-    let total = memory_lines
-        .iter()
-        .map(|memory| {
-            memory
-                .iter()
-                .map(|instruction| instruction.product(true))
-                .sum::<u32>()
+    tokens
+        .into_iter()
+        .map(|token| {
+            let value = (token.definition.execute)(&mut machine, &token.args);
+            Executed { token, value }
         })
-        .sum::<u32>();
+        .collect()
+}
+
+pub fn parse(input: &str) -> Vec<Token> {
+    tokenize(input, INSTRUCTIONS)
+}
 
-    Some(total)
+/// The instructions executed in part one, which only knows about multiplications.
+pub fn trace_part_one(input: &str) -> Vec<Executed> {
+    run(tokenize(input, &[MUL]))
+}
+
+/// The instructions executed in part two, where `do()` and `don't()` toggle multiplications.
+pub fn trace_part_two(input: &str) -> Vec<Executed> {
+    run(parse(input))
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(
+        trace_part_one(input)
+            .iter()
+            .filter_map(|executed| executed.value)
+            .sum(),
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let memory_lines = parse(input);
+    Some(
+        trace_part_two(input)
+            .iter()
+            .filter_map(|executed| executed.value)
+            .sum(),
+    )
+}
 
-    let total = memory_lines
-        .iter()
-        .map(|memory| {
-            memory
-                .iter()
-                .map(|instruction| instruction.product(false))
-                .sum::<u32>()
-        })
-        .sum::<u32>();
+/// Generates `size` lines of corrupted memory mixing valid and broken instructions.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let noise = [
+        'x', '%', '&', '*', '[', ']', '(', ')', ',', ' ', '!', '@', '^', '<', '>',
+    ];
 
-    Some(total)
+    (0..size)
+        .map(|_| {
+            let mut line = String::new();
+            for _ in 0..rng.usize(10..30) {
+                match rng.u8(0..8) {
+                    0 => line.push_str("do()"),
+                    1 => line.push_str("don't()"),
+                    2 => line.push_str(&format!("mul({},{}]", rng.u32(1..1000), rng.u32(1..1000))),
+                    3 => line.push_str(&format!(
+                        "mul ( {},{} )",
+                        rng.u32(1..1000),
+                        rng.u32(1..1000)
+                    )),
+                    _ => line.push_str(&format!("mul({},{})", rng.u32(1..1000), rng.u32(1..1000))),
+                }
+                for _ in 0..rng.usize(0..4) {
+                    line.push(noise[rng.usize(..noise.len())]);
+                }
+            }
+            line + "\n"
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -105,4 +233,120 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(48));
     }
+
+    #[test]
+    fn test_trace() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let trace: Vec<String> = trace_part_two(&input)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            trace,
+            vec![
+                "     1: mul(2,4) = 8",
+                "    20: don't()",
+                "    28: mul(5,5)",
+                "    48: mul(11,8)",
+                "    59: do()",
+                "    64: mul(8,5) = 40",
+            ]
+        );
+
+        let counted: Vec<usize> = trace_part_one(&input)
+            .iter()
+            .filter(|executed| executed.value.is_some())
+            .map(|executed| executed.token.offset)
+            .collect();
+        assert_eq!(counted, vec![1, 28, 48, 64]);
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        const ADD: Definition = Definition {
+            name: "add",
+            arity: 3,
+            execute: |_, args| Some(args.iter().sum()),
+        };
+        let tokens = tokenize("mul(2,3)add(1,2,3)add(1,2)", &[MUL, ADD]);
+        let values: Vec<Option<u32>> = run(tokens).iter().map(|executed| executed.value).collect();
+        assert_eq!(values, vec![Some(6), Some(6)]);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Piece {
+        Mul(u32, u32),
+        Do,
+        Dont,
+        Noise(String),
+    }
+
+    /// Generates corrupted memory from valid instructions interleaved with noise
+    /// that cannot form an instruction on its own.
+    fn memory() -> impl Strategy<Value = Vec<Piece>> {
+        let piece = prop_oneof![
+            (0..1000_u32, 0..1000_u32).prop_map(|(a, b)| Piece::Mul(a, b)),
+            Just(Piece::Do),
+            Just(Piece::Dont),
+            "[xyz!@#%&*,() ]{1,6}".prop_map(Piece::Noise),
+        ];
+        prop::collection::vec(piece, 0..40)
+    }
+
+    fn to_input(pieces: &[Piece]) -> String {
+        pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Mul(a, b) => format!("mul({a},{b})"),
+                Piece::Do => "do()".into(),
+                Piece::Dont => "don't()".into(),
+                Piece::Noise(noise) => noise.clone(),
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[mul(dont')0-9,\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(pieces in memory()) {
+            let parsed: Vec<(u32, u32)> = parse(&to_input(&pieces))
+                .iter()
+                .filter(|token| token.definition.name == "mul")
+                .map(|token| (token.args[0], token.args[1]))
+                .collect();
+            let expected: Vec<(u32, u32)> = pieces
+                .iter()
+                .filter_map(|piece| match piece {
+                    Piece::Mul(a, b) => Some((*a, *b)),
+                    _ => None,
+                })
+                .collect();
+            prop_assert_eq!(parsed, expected);
+        }
+
+        #[test]
+        fn test_parts_match_tokens(pieces in memory()) {
+            let (mut all, mut enabled_only, mut enabled) = (0, 0, true);
+            for piece in &pieces {
+                match piece {
+                    Piece::Mul(a, b) => {
+                        all += a * b;
+                        if enabled {
+                            enabled_only += a * b;
+                        }
+                    }
+                    Piece::Do => enabled = true,
+                    Piece::Dont => enabled = false,
+                    Piece::Noise(_) => {}
+                }
+            }
+            let input = to_input(&pieces);
+            prop_assert_eq!(part_one(&input), Some(all));
+            prop_assert_eq!(part_two(&input), Some(enabled_only));
+        }
+    }
 }
@@ -1,209 +1,108 @@
-advent_of_code::solution!(4);
-
-#[derive(PartialEq, Copy, Clone, Debug)]
-pub enum Letter {
-    X,
-    M,
-    A,
-    S,
-}
+use advent_of_code::grid::{Grid, ALL_DIRECTIONS, ORTHOGONAL};
+use advent_of_code::parse::ParseError;
+use advent_of_code::word_search::{find_pattern, find_word, parse_pattern};
+
+advent_of_code::solution!(
+    4,
+    parse = parse,
+    reference_one = reference_part_one,
+    reference_two = reference_part_two,
+    generate = generate,
+);
+
+/// Parses the word search into the shared grid. Any letters are allowed, but rows have to be of
+/// the same length.
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    let rows: Vec<&str> = input
+        .split('\n')
+        .map(str::trim_end)
+        .filter(|row| !row.is_empty())
+        .collect();
 
-impl From<char> for Letter {
-    fn from(c: char) -> Self {
-        match c {
-            'X' => Letter::X,
-            'M' => Letter::M,
-            'A' => Letter::A,
-            'S' => Letter::S,
-            _ => panic!("Invalid letter"),
-        }
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if let Some(row_index) = rows.iter().position(|row| row.chars().count() != width) {
+        return Err(ParseError::new(row_index + 1, "rows differ in length"));
     }
-}
 
-#[derive(Copy, Clone, Debug)]
-pub struct ElfLetter {
-    pub row: usize,
-    pub column: usize,
-    pub letter: Letter,
+    Ok(Grid::parse(input, |_, c| c))
 }
 
-impl ElfLetter {
-    pub fn new(row: usize, column: usize, letter: Letter) -> Self {
-        Self {
-            row,
-            column,
-            letter,
-        }
-    }
-
-    pub fn is_part_one_valid_distance(&self, next: &ElfLetter, x_letter: &ElfLetter) -> bool {
-        if next.letter == Letter::A || next.letter == Letter::S {
-            let (first_direction, second_direction) = self.get_directions(x_letter, next);
-
-            if first_direction != second_direction {
-                return false;
-            }
-        }
-
-        let (row_distance, column_distance) = self.get_distances(next);
-
-        row_distance <= 1 && column_distance <= 1
-    }
-
-    pub fn is_part_two_valid_distance(&self, next: &ElfLetter, m_letter: &ElfLetter) -> bool {
-        if next.letter == Letter::S {
-            let (first_direction, second_direction) = self.get_directions(m_letter, next);
-
-            if first_direction != second_direction {
-                return false;
-            }
-        }
-
-        let (row_distance, column_distance) = self.get_distances(next);
-
-        row_distance == 1 && column_distance == 1
-    }
-
-    fn get_directions(&self, first: &ElfLetter, next: &ElfLetter) -> (isize, isize) {
-        let first_direction = (
-            first.row as isize - self.row as isize,
-            first.column as isize - self.column as isize,
-        );
-        let next_direction = (
-            next.row as isize - self.row as isize,
-            next.column as isize - self.column as isize,
-        );
-
-        (
-            first_direction.0 * next_direction.1,
-            first_direction.1 * next_direction.0,
-        )
-    }
+pub fn part_one(input: &str) -> Option<u32> {
+    let grid = parse(input).ok()?;
+    let word: Vec<char> = "XMAS".chars().collect();
 
-    fn get_distances(&self, next: &ElfLetter) -> (usize, usize) {
-        let row_distance = if self.row > next.row {
-            self.row - next.row
-        } else {
-            next.row - self.row
-        };
-        let column_distance = if self.column > next.column {
-            self.column - next.column
-        } else {
-            next.column - self.column
-        };
-
-        (row_distance, column_distance)
-    }
+    Some(find_word(&grid, &word).len() as u32)
 }
 
-pub fn parse(input: &str) -> Vec<ElfLetter> {
-    let elf_letters = input
-        .split('\n')
-        .filter(|row| !row.is_empty())
-        .enumerate()
-        .flat_map(|(row_index, row)| {
-            row.split("")
-                .skip(1)
-                .take(row.len())
-                .enumerate()
-                .map(|(col_index, elf_letter)| {
-                    let letter = Letter::from(elf_letter.chars().next().unwrap());
-                    ElfLetter::new(row_index, col_index, letter)
-                })
-                .collect::<Vec<ElfLetter>>()
-        })
-        .collect();
+pub fn part_two(input: &str) -> Option<u32> {
+    let grid = parse(input).ok()?;
+    // every rotation of the cross is a distinct way of writing two diagonal MAS.
+    let cross = parse_pattern("M.S\n.A.\nM.S", '.');
 
-    elf_letters
+    Some(find_pattern(&grid, &cross, &ORTHOGONAL).len() as u32)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let elf_letters = parse(input);
+/// Direct grid scan for XMAS in all eight directions, used to cross-check [`part_one`].
+pub fn reference_part_one(input: &str) -> Option<u32> {
+    let grid = parse(input).ok()?;
 
-    let x_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::X)
-        .collect();
-    let m_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::M)
-        .collect();
-    let a_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::A)
-        .collect();
-    let s_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::S)
-        .collect();
-
-    let mut valid_word_counts = 0;
-
-    x_letters.iter().for_each(|elf_letter| {
-        for m_letter in m_letters.iter() {
-            if elf_letter.is_part_one_valid_distance(m_letter, elf_letter) {
-                for a_letter in a_letters.iter() {
-                    if m_letter.is_part_one_valid_distance(a_letter, elf_letter) {
-                        for s_letter in s_letters.iter() {
-                            if a_letter.is_part_one_valid_distance(s_letter, elf_letter) {
-                                valid_word_counts += 1
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    });
+    let count = grid
+        .positions()
+        .flat_map(|start| {
+            ALL_DIRECTIONS
+                .iter()
+                .map(move |direction| (start, direction))
+        })
+        .filter(|(start, direction)| {
+            let mut position = Some(*start);
+            "XMAS".chars().all(|letter| {
+                let matches = position.is_some_and(|p| grid.get(p) == Some(&letter));
+                position = position.and_then(|p| grid.offset(p, **direction));
+                matches
+            })
+        })
+        .count();
 
-    Some(valid_word_counts as u32)
+    Some(count as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let elf_letters = parse(input);
+/// Direct grid scan for MAS crosses around every `A`, used to cross-check [`part_two`].
+pub fn reference_part_two(input: &str) -> Option<u32> {
+    let grid = parse(input).ok()?;
+    let corner = |position, offset| grid.offset(position, offset).and_then(|p| grid.get(p));
+    let is_mas = |a: Option<&char>, b: Option<&char>| {
+        matches!((a, b), (Some('M'), Some('S')) | (Some('S'), Some('M')))
+    };
 
-    let m_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::M)
-        .collect();
-    let a_letters: Vec<&ElfLetter> = elf_letters
+    let count = grid
         .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::A)
-        .collect();
-    let s_letters: Vec<&ElfLetter> = elf_letters
-        .iter()
-        .filter(|elf_letter| elf_letter.letter == Letter::S)
-        .collect();
-
-    let mut diagonal_words = vec![];
-
-    m_letters.iter().for_each(|elf_letter| {
-        for a_letter in a_letters.iter() {
-            if elf_letter.is_part_two_valid_distance(a_letter, elf_letter) {
-                for s_letter in s_letters.iter() {
-                    if a_letter.is_part_two_valid_distance(s_letter, elf_letter) {
-                        diagonal_words.push([elf_letter, a_letter, s_letter]);
-                    }
-                }
-            }
-        }
-    });
-
-    let mut total_num_xmas = 0;
+        .filter(|(position, letter)| {
+            **letter == 'A'
+                && is_mas(corner(*position, (-1, -1)), corner(*position, (1, 1)))
+                && is_mas(corner(*position, (-1, 1)), corner(*position, (1, -1)))
+        })
+        .count();
 
-    for (index, diagonal_word) in diagonal_words.iter().enumerate() {
-        for word in diagonal_words[(index + 1)..].iter() {
-            if diagonal_word[1].row == word[1].row && diagonal_word[1].column == word[1].column {
-                total_num_xmas += 1;
-            }
-        }
-    }
+    Some(count as u32)
+}
 
-    Some(total_num_xmas as u32)
+/// Generates a square word search of the given size filled with random letters of XMAS.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    (0..size)
+        .map(|_| {
+            let row: String = (0..size)
+                .map(|_| ['X', 'M', 'A', 'S'][rng.usize(..4)])
+                .collect();
+            row + "\n"
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -216,4 +115,44 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(9));
     }
+
+    #[test]
+    fn test_parse_rejects_ragged_rows() {
+        assert_eq!(
+            parse("XMAS\nXM\n").err(),
+            Some(ParseError::new(2, "rows differ in length"))
+        );
+    }
+
+    fn letter_grids() -> impl Strategy<Value = Vec<String>> {
+        (1..10_usize, 1..10_usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(
+                prop::string::string_regex(&format!("[XMASZ]{{{width}}}")).unwrap(),
+                height,
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[XMAS\\n.]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(rows in letter_grids()) {
+            let grid = parse(&rows.join("\n")).unwrap();
+            prop_assert_eq!(grid.to_string(), rows.join("\n"));
+        }
+
+        #[test]
+        fn test_part_one_matches_reference(rows in letter_grids()) {
+            prop_assert_eq!(part_one(&rows.join("\n")), reference_part_one(&rows.join("\n")));
+        }
+
+        #[test]
+        fn test_part_two_matches_reference(rows in letter_grids()) {
+            prop_assert_eq!(part_two(&rows.join("\n")), reference_part_two(&rows.join("\n")));
+        }
+    }
 }
@@ -1,100 +1,170 @@
-use std::collections::HashMap;
+use advent_of_code::graph::Graph;
+use advent_of_code::parse::{parse_number, ParseError};
+use std::{collections::HashMap, fmt};
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, parse = parse, generate = generate);
 
-pub fn parse(input: &str) -> (HashMap<u32, Vec<u32>>, Vec<Vec<u32>>) {
-    let sections: Vec<&str> = input
-        .split("\n\n")
-        .filter(|section| !section.is_empty())
-        .collect();
+pub type Rules = HashMap<u32, Vec<u32>>;
 
-    let mut rules: HashMap<u32, Vec<u32>> = HashMap::new();
+pub fn parse(input: &str) -> Result<(Rules, Vec<Vec<u32>>), ParseError> {
+    let (rules_section, pages_section) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(0, "expected rules and updates separated by an empty line")
+    })?;
+    let pages_offset = rules_section.split('\n').count() + 2;
 
-    sections[0]
-        .split('\n')
-        .filter(|rule| !rule.is_empty())
-        .for_each(|rule| {
-            let line_rules = rule
-                .split("|")
-                .map(|rule| rule.parse::<u32>().unwrap())
-                .collect::<Vec<u32>>();
-            rules.entry(line_rules[0]).or_default().push(line_rules[1]);
-        });
-
-    let pages = sections[1]
+    let mut rules: Rules = HashMap::new();
+
+    for (line_index, rule) in rules_section.split('\n').enumerate() {
+        if rule.trim().is_empty() {
+            continue;
+        }
+        let (before, after) = rule
+            .split_once('|')
+            .ok_or_else(|| ParseError::new(line_index + 1, format!("invalid rule `{rule}`")))?;
+        rules
+            .entry(parse_number(before, line_index + 1)?)
+            .or_default()
+            .push(parse_number(after, line_index + 1)?);
+    }
+
+    let pages = pages_section
         .split('\n')
-        .filter(|page| !page.is_empty())
-        .map(|page| {
-            page.split(",")
-                .map(|page| page.parse::<u32>().unwrap())
+        .enumerate()
+        .filter(|(_, page)| !page.trim().is_empty())
+        .map(|(line_index, page)| {
+            page.split(',')
+                .map(|page| parse_number(page, line_index + pages_offset))
                 .collect()
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (rules, pages)
+    Ok((rules, pages))
 }
 
-pub fn get_page_set_checks(rules: &HashMap<u32, Vec<u32>>, pages: &[Vec<u32>]) -> Vec<bool> {
+/// An ordering rule `before|after`: `before` has to be printed before `after`.
+pub type Rule = (u32, u32);
+
+/// Pages whose rules require each of them to be printed before the next one, returning to the
+/// first page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle(pub Vec<u32>);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pages: Vec<String> = self.0.iter().map(u32::to_string).collect();
+        write!(f, "rules form a cycle: {}", pages.join(" → "))
+    }
+}
+
+/// Checks every update against the rules, returning the first rule an invalid update violates
+/// and [`None`] for updates that are already in the right order.
+pub fn get_page_set_checks(rules: &Rules, pages: &[Vec<u32>]) -> Vec<Option<Rule>> {
     pages
         .iter()
         .map(|page_set| {
-            let mut valid = true;
-            let reversed_set: Vec<&u32> = page_set.iter().rev().collect();
-            'outer_loop: for (index, page) in reversed_set.iter().enumerate() {
-                for check_page in &reversed_set[(index + 1)..] {
-                    if rules.get(page).unwrap_or(&vec![]).contains(check_page) {
-                        valid = false;
-                        break 'outer_loop;
-                    }
-                }
-            }
-            valid
+            page_set.iter().enumerate().find_map(|(index, &before)| {
+                // a page printed earlier that the rules require to come after this one.
+                let must_follow = rules.get(&before)?;
+                page_set[..index]
+                    .iter()
+                    .find(|after| must_follow.contains(after))
+                    .map(|&after| (before, after))
+            })
         })
         .collect()
 }
 
+/// Reorders `update` so that it satisfies every rule between its pages, by sorting the rules
+/// restricted to the update topologically. Pages without a rule between them keep their order in
+/// the update. Fails if those rules contradict each other.
+pub fn corrected_update(rules: &Rules, update: &[u32]) -> Result<Vec<u32>, Cycle> {
+    let mut graph = Graph::new();
+    for &page in update {
+        graph.add_node(page);
+        for &after in rules.get(&page).into_iter().flatten() {
+            if update.contains(&after) {
+                graph.add_edge(page, after, 1);
+            }
+        }
+    }
+
+    graph.topological_sort_from(update).map_err(Cycle)
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let (rules, pages) = parse(input);
+    let (rules, pages) = parse(input).ok()?;
 
     let page_set_checks = get_page_set_checks(&rules, &pages);
     let page_number_sum: u32 = pages
         .iter()
-        .enumerate()
-        .filter(|(index, _)| page_set_checks[*index])
-        .map(|(_, page_set)| page_set[(page_set.len() - 1) / 2])
+        .zip(page_set_checks)
+        .filter(|(_, violation)| violation.is_none())
+        .map(|(page_set, _)| page_set[(page_set.len() - 1) / 2])
         .sum();
 
     Some(page_number_sum)
 }
 
+/// Updates whose rules form a cycle cannot be put in order. Their cycle is reported and they are
+/// left out of the sum.
 pub fn part_two(input: &str) -> Option<u32> {
-    let (rules, pages) = parse(input);
+    let (rules, pages) = parse(input).ok()?;
 
     let page_set_checks = get_page_set_checks(&rules, &pages);
-    let page_number_sum: u32 = pages
+    let page_number_sum = pages
         .iter()
         .enumerate()
-        .filter(|(index, _)| !page_set_checks[*index])
-        .map(|(_, page_set)| {
-            let mut sorted_pages = page_set.clone();
-            sorted_pages.sort_by(|a, b| {
-                let default_value = Vec::new();
-                let a_rules = rules.get(a).unwrap_or(&default_value);
-                if a_rules.contains(b) {
-                    return std::cmp::Ordering::Less;
+        .zip(page_set_checks)
+        .filter(|(_, violation)| violation.is_some())
+        .filter_map(
+            |((index, page_set), _)| match corrected_update(&rules, page_set) {
+                Ok(sorted_pages) => Some(sorted_pages),
+                Err(cycle) => {
+                    eprintln!("Skipping update {}: {cycle}", index + 1);
+                    None
                 }
-                std::cmp::Ordering::Equal
-            });
-            sorted_pages[(page_set.len() - 1) / 2]
-        })
+            },
+        )
+        .map(|sorted_pages| sorted_pages[(sorted_pages.len() - 1) / 2])
         .sum();
 
     Some(page_number_sum)
 }
 
+/// Generates a total order of up to 90 pages with every pairwise rule and `size` shuffled updates.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let mut order: Vec<u32> = (10..100).collect();
+    rng.shuffle(&mut order);
+    order.truncate((size + 4).min(order.len()));
+
+    let mut output = String::new();
+    for (index, before) in order.iter().enumerate() {
+        for after in &order[index + 1..] {
+            output.push_str(&format!("{before}|{after}\n"));
+        }
+    }
+    output.push('\n');
+
+    for _ in 0..size {
+        let length = (2 * rng.usize(0..12) + 1).min((order.len() - 1) | 1);
+        let mut update = order.clone();
+        rng.shuffle(&mut update);
+        update.truncate(length);
+        if rng.bool() {
+            update.sort_by_key(|page| order.iter().position(|x| x == page));
+        }
+        let pages: Vec<String> = update.iter().map(u32::to_string).collect();
+        output.push_str(&(pages.join(",") + "\n"));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -107,4 +177,117 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_get_page_set_checks() {
+        let (rules, pages) = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(
+            get_page_set_checks(&rules, &pages),
+            vec![
+                None,
+                None,
+                None,
+                Some((97, 75)),
+                Some((29, 13)),
+                Some((75, 13))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_corrected_update() {
+        let (rules, _) = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(
+            corrected_update(&rules, &[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+
+        // only 1|2 orders these pages, the others stay where they were.
+        let (rules, _) = parse("1|2\n\n4,2,3,1\n").unwrap();
+        assert_eq!(
+            corrected_update(&rules, &[4, 2, 3, 1]),
+            Ok(vec![4, 3, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_corrected_update_reports_cycles() {
+        let (rules, _) = parse("1|2\n2|3\n3|1\n\n1,2,3\n").unwrap();
+        let cycle = corrected_update(&rules, &[1, 2, 3]).unwrap_err();
+        assert_eq!(cycle.0.len(), 4);
+        assert_eq!(cycle.0.first(), cycle.0.last());
+        assert!(cycle.to_string().starts_with("rules form a cycle: "));
+
+        // the cycle is only a problem for updates containing all of its pages.
+        assert_eq!(corrected_update(&rules, &[3, 2]), Ok(vec![2, 3]));
+        // only the update with the whole cycle is skipped.
+        assert_eq!(part_two("1|2\n2|3\n3|1\n\n3,2,1\n3,2\n"), Some(2));
+    }
+
+    /// Generates a random total order over distinct pages, every rule it implies
+    /// and a set of odd-length updates drawn from it in random order.
+    fn manuals() -> impl Strategy<Value = (Vec<u32>, Vec<Vec<u32>>)> {
+        Just((10..100).collect::<Vec<u32>>())
+            .prop_shuffle()
+            .prop_flat_map(|pages| {
+                let order = pages[..12].to_vec();
+                let update = prop::sample::subsequence(order.clone(), 1..12)
+                    .prop_filter("updates have a middle page", |update| update.len() % 2 == 1)
+                    .prop_shuffle();
+                (Just(order), prop::collection::vec(update, 1..10))
+            })
+    }
+
+    fn to_input(order: &[u32], updates: &[Vec<u32>]) -> String {
+        let mut lines = vec![];
+        for (index, before) in order.iter().enumerate() {
+            for after in &order[index + 1..] {
+                lines.push(format!("{before}|{after}"));
+            }
+        }
+        lines.push(String::new());
+        for update in updates {
+            let pages: Vec<String> = update.iter().map(u32::to_string).collect();
+            lines.push(pages.join(","));
+        }
+        lines.join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9|,\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips((order, updates) in manuals()) {
+            let (rules, pages) = parse(&to_input(&order, &updates)).unwrap();
+            prop_assert_eq!(pages, updates);
+            for (index, before) in order.iter().enumerate() {
+                let mut expected = order[index + 1..].to_vec();
+                let mut after = rules.get(before).cloned().unwrap_or_default();
+                expected.sort_unstable();
+                after.sort_unstable();
+                prop_assert_eq!(after, expected);
+            }
+        }
+
+        #[test]
+        fn test_parts_match_reference((order, updates) in manuals()) {
+            let position = |page: &u32| order.iter().position(|p| p == page).unwrap();
+            let (mut correct, mut reordered) = (0, 0);
+            for update in &updates {
+                let mut sorted = update.clone();
+                sorted.sort_by_key(position);
+                if sorted == *update {
+                    correct += sorted[sorted.len() / 2];
+                } else {
+                    reordered += sorted[sorted.len() / 2];
+                }
+            }
+            let input = to_input(&order, &updates);
+            prop_assert_eq!(part_one(&input), Some(correct));
+            prop_assert_eq!(part_two(&input), Some(reordered));
+        }
+    }
 }
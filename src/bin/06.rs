@@ -1,6 +1,16 @@
-advent_of_code::solution!(6);
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+use advent_of_code::export::{Frames, Palette, Rgb};
+use advent_of_code::grid::{self, Position};
+use advent_of_code::parse::ParseError;
+use std::collections::{HashMap, HashSet};
+
+advent_of_code::solution!(
+    6,
+    parse = parse,
+    export = export_frames,
+    generate = generate
+);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Direction {
     UP,
     DOWN,
@@ -8,6 +18,48 @@ pub enum Direction {
     RIGHT,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::UP,
+        Direction::DOWN,
+        Direction::LEFT,
+        Direction::RIGHT,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::UP => Direction::RIGHT,
+            Direction::RIGHT => Direction::DOWN,
+            Direction::DOWN => Direction::LEFT,
+            Direction::LEFT => Direction::UP,
+        }
+    }
+
+    /// The neighbouring position in this direction, if it is still on a `width` by `height` map.
+    pub fn step(self, (row, col): Position, width: usize, height: usize) -> Option<Position> {
+        let (row, col) = match self {
+            Direction::UP => (row.checked_sub(1)?, col),
+            Direction::DOWN => (row + 1, col),
+            Direction::LEFT => (row, col.checked_sub(1)?),
+            Direction::RIGHT => (row, col + 1),
+        };
+        (row < height && col < width).then_some((row, col))
+    }
+
+    /// How many steps in this direction lead from `from` to `to`, if `to` lies strictly ahead.
+    fn distance(self, from: Position, to: Position) -> Option<usize> {
+        match self {
+            Direction::UP => (from.1 == to.1 && to.0 < from.0).then(|| from.0 - to.0),
+            Direction::DOWN => (from.1 == to.1 && to.0 > from.0).then(|| to.0 - from.0),
+            Direction::LEFT => (from.0 == to.0 && to.1 < from.1).then(|| from.1 - to.1),
+            Direction::RIGHT => (from.0 == to.0 && to.1 > from.1).then(|| to.1 - from.1),
+        }
+    }
+}
+
+/// A guard's position together with the direction they are facing.
+pub type State = (Position, Direction);
+
 pub struct Grid {
     pub rows: Vec<GridRow>,
     pub guard: Guard,
@@ -18,37 +70,57 @@ impl Grid {
         Grid { rows, guard }
     }
 
-    pub fn update_grid(&mut self) {
-        let new_direction = self.guard.get_new_direction(&self.rows);
-        self.guard.direction = new_direction;
-        self.rows[self.guard.row].cells[self.guard.col].cell_type = CellType::STEP;
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.cells.len())
+    }
 
-        if !self.is_obstacle_in_direction(new_direction) {
-            self.guard.move_in_direction();
-        }
+    pub fn height(&self) -> usize {
+        self.rows.len()
     }
 
-    pub fn is_obstacle_in_direction(&self, direction: Direction) -> bool {
-        let (new_row, new_col) = match direction {
-            Direction::UP => (self.guard.row.saturating_sub(1), self.guard.col),
-            Direction::RIGHT => (self.guard.row, self.guard.col + 1),
-            Direction::DOWN => (self.guard.row + 1, self.guard.col),
-            Direction::LEFT => (self.guard.row, self.guard.col.saturating_sub(1)),
-        };
+    pub fn is_obstacle(&self, (row, col): Position) -> bool {
+        self.rows[row].cells[col].cell_type == CellType::OBSTACLE
+    }
 
-        if new_row == 0
-            || new_row == self.rows.len() - 1
-            || new_col == 0
-            || new_col == self.rows[0].cells.len() - 1
-        {
-            return false;
-        }
+    /// The cells of the loop the guard gets stuck in once an obstacle is added at `obstacle`, in
+    /// walking order, or [`None`] if the guard still leaves the map.
+    pub fn loop_path(&self, obstacle: Position) -> Option<Vec<Position>> {
+        let mut walk = GuardWalk::with_obstacle(self, obstacle);
+        let mut positions: Vec<Position> = walk.by_ref().map(|(position, _, _)| position).collect();
 
-        if new_row < self.rows.len() && new_col < self.rows[0].cells.len() {
-            self.rows[new_row].cells[new_col].cell_type == CellType::OBSTACLE
-        } else {
-            true
+        let Some(Outcome::Looped { cycle_start, .. }) = walk.outcome() else {
+            return None;
+        };
+        positions.drain(..cycle_start);
+        // turning in place repeats the position, and the loop returns to its first cell.
+        positions.dedup();
+        if positions.len() > 1 && positions.first() == positions.last() {
+            positions.pop();
         }
+        Some(positions)
+    }
+
+    /// Converts the grid into the shared grid representation, marking the guard with `^`.
+    pub fn snapshot(&self) -> grid::Grid<char> {
+        let width = self.rows.first().map_or(0, |row| row.cells.len());
+        let cells = self
+            .rows
+            .iter()
+            .flat_map(|row| row.cells.iter())
+            .map(|cell| {
+                if cell.row == self.guard.row && cell.col == self.guard.col {
+                    '^'
+                } else {
+                    match cell.cell_type {
+                        CellType::EMPTY => '.',
+                        CellType::OBSTACLE => '#',
+                        CellType::STEP => 'X',
+                    }
+                }
+            })
+            .collect();
+
+        grid::Grid::new(width, self.rows.len(), cells)
     }
 }
 
@@ -90,176 +162,336 @@ impl Guard {
             col,
         }
     }
+}
 
-    pub fn get_new_direction(&self, rows: &[GridRow]) -> Direction {
-        match self.direction {
-            Direction::UP => {
-                if rows[self.row - 1].cells[self.col].cell_type == CellType::OBSTACLE {
-                    Direction::RIGHT
-                } else {
-                    Direction::UP
-                }
-            }
-            Direction::RIGHT => {
-                if rows[self.row].cells[self.col + 1].cell_type == CellType::OBSTACLE {
-                    Direction::DOWN
-                } else {
-                    Direction::RIGHT
-                }
-            }
-            Direction::DOWN => {
-                if rows[self.row + 1].cells[self.col].cell_type == CellType::OBSTACLE {
-                    Direction::LEFT
-                } else {
-                    Direction::DOWN
+/// Moves the guard one straight segment at a time: for every cell and direction, this stores the
+/// cell in front of the next obstacle, or [`None`] if the guard walks off the map first.
+pub struct Jumps {
+    width: usize,
+    height: usize,
+    stops: Vec<[Option<Position>; 4]>,
+}
+
+impl Jumps {
+    pub fn new(grid: &Grid) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let mut stops = vec![[None; 4]; width * height];
+
+        for direction in Direction::ALL {
+            // visit the cells ahead first, so that their stops are known already.
+            let rows: Vec<usize> = match direction {
+                Direction::DOWN => (0..height).rev().collect(),
+                _ => (0..height).collect(),
+            };
+            let cols: Vec<usize> = match direction {
+                Direction::RIGHT => (0..width).rev().collect(),
+                _ => (0..width).collect(),
+            };
+
+            for &row in &rows {
+                for &col in &cols {
+                    stops[row * width + col][direction as usize] =
+                        match direction.step((row, col), width, height) {
+                            None => None,
+                            Some(next) if grid.is_obstacle(next) => Some((row, col)),
+                            Some(next) => stops[next.0 * width + next.1][direction as usize],
+                        };
                 }
             }
-            Direction::LEFT => {
-                if rows[self.row].cells[self.col - 1].cell_type == CellType::OBSTACLE {
-                    Direction::UP
-                } else {
-                    Direction::LEFT
-                }
+        }
+
+        Jumps {
+            width,
+            height,
+            stops,
+        }
+    }
+
+    /// Where a guard walking from `position` stops, taking an additional `obstacle` into account.
+    pub fn stop(
+        &self,
+        position: Position,
+        direction: Direction,
+        obstacle: Option<Position>,
+    ) -> Option<Position> {
+        let stop = self.stops[position.0 * self.width + position.1][direction as usize];
+        let Some(distance) = obstacle.and_then(|obstacle| direction.distance(position, obstacle))
+        else {
+            return stop;
+        };
+
+        let stop_distance = stop.map(|stop| direction.distance(position, stop).unwrap_or(0));
+        if stop_distance.is_some_and(|stop_distance| stop_distance < distance) {
+            return stop;
+        }
+
+        let mut position = position;
+        for _ in 1..distance {
+            position = direction.step(position, self.width, self.height)?;
+        }
+        Some(position)
+    }
+
+    /// Follows the guard from `start`, with an additional `obstacle`, and returns every state in
+    /// which they turn (after the starting state). If the guard never leaves the map, the index of
+    /// the first state of the repeating loop is returned as well.
+    pub fn walk(&self, start: State, obstacle: Option<Position>) -> (Vec<State>, Option<usize>) {
+        let mut turns = vec![start];
+        let mut seen = HashMap::from([(start, 0)]);
+        let (mut position, mut direction) = start;
+
+        while let Some(stop) = self.stop(position, direction, obstacle) {
+            (position, direction) = (stop, direction.turn_right());
+            if let Some(&index) = seen.get(&(position, direction)) {
+                return (turns, Some(index));
             }
+            seen.insert((position, direction), turns.len());
+            turns.push((position, direction));
+        }
+
+        (turns, None)
+    }
+}
+
+/// How a [`GuardWalk`] ended.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    /// The guard walked off the map.
+    Exited,
+    /// The guard got stuck: from step `cycle_start` on, the same `cycle_len` steps repeat forever.
+    Looped {
+        cycle_start: usize,
+        cycle_len: usize,
+    },
+}
+
+/// Walks the guard across the map one step at a time, yielding their position and direction
+/// after each step, starting with the initial state, and whether they turned to get there.
+/// Once exhausted, [`GuardWalk::outcome`] tells whether the guard left the map or got stuck.
+pub struct GuardWalk<'a> {
+    grid: &'a Grid,
+    obstacle: Option<Position>,
+    next: Option<(State, bool)>,
+    seen: HashMap<State, usize>,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> GuardWalk<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        let start = ((grid.guard.row, grid.guard.col), grid.guard.direction);
+        GuardWalk {
+            grid,
+            obstacle: None,
+            next: Some((start, false)),
+            seen: HashMap::new(),
+            outcome: None,
         }
     }
 
-    pub fn move_in_direction(&mut self) {
-        match self.direction {
-            Direction::UP => self.row -= 1,
-            Direction::RIGHT => self.col += 1,
-            Direction::DOWN => self.row += 1,
-            Direction::LEFT => self.col -= 1,
+    /// Walks the guard as if an additional obstacle was placed at `obstacle`.
+    pub fn with_obstacle(grid: &'a Grid, obstacle: Position) -> Self {
+        GuardWalk {
+            obstacle: Some(obstacle),
+            ..Self::new(grid)
         }
     }
 
-    pub fn is_at_exit(&self, rows: &[GridRow]) -> bool {
-        self.row == 0
-            || self.row == rows.len() - 1
-            || self.col == 0
-            || self.col == rows[0].cells.len() - 1
+    /// How the walk ended, [`None`] while the guard is still walking.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    /// Walks the guard until the end and returns the outcome.
+    pub fn finish(mut self) -> Outcome {
+        self.by_ref().for_each(drop);
+        self.outcome.unwrap_or(Outcome::Exited)
+    }
+
+    fn is_blocked(&self, position: Position) -> bool {
+        self.grid.is_obstacle(position) || self.obstacle == Some(position)
+    }
+}
+
+impl Iterator for GuardWalk<'_> {
+    type Item = (Position, Direction, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (state, turned) = self.next.take()?;
+        let steps = self.seen.len();
+
+        if let Some(&cycle_start) = self.seen.get(&state) {
+            self.outcome = Some(Outcome::Looped {
+                cycle_start,
+                cycle_len: steps - cycle_start,
+            });
+            return None;
+        }
+        self.seen.insert(state, steps);
+
+        let (position, direction) = state;
+        self.next = match direction.step(position, self.grid.width(), self.grid.height()) {
+            None => {
+                self.outcome = Some(Outcome::Exited);
+                None
+            }
+            Some(next) if self.is_blocked(next) => Some(((position, direction.turn_right()), true)),
+            Some(next) => Some(((next, direction), false)),
+        };
+
+        Some((position, direction, turned))
     }
 }
 
-pub fn parse(input: &str) -> Grid {
-    let mut guard = Guard::new(0, 0);
-    let rows = input
+pub fn parse(input: &str) -> Result<Grid, ParseError> {
+    let mut guard = None;
+    let mut rows: Vec<GridRow> = vec![];
+
+    for (row_index, row) in input
         .split('\n')
+        .map(str::trim_end)
         .filter(|row| !row.is_empty())
         .enumerate()
-        .map(|(row_index, row)| {
-            let grid_row: Vec<GridCell> = row
-                .chars()
-                .enumerate()
-                .map(|(col_index, cell): (usize, char)| match cell {
-                    '.' => GridCell {
-                        cell_type: CellType::EMPTY,
-                        row: row_index,
-                        col: col_index,
-                    },
-                    '#' => GridCell {
-                        cell_type: CellType::OBSTACLE,
-                        row: row_index,
-                        col: col_index,
-                    },
+    {
+        let grid_row = row
+            .chars()
+            .enumerate()
+            .map(|(col_index, cell)| {
+                let cell_type = match cell {
+                    '.' => CellType::EMPTY,
+                    '#' => CellType::OBSTACLE,
                     '^' => {
-                        guard =
-                            Guard::new(row_index, row.chars().position(|ch| ch == '^').unwrap());
-                        GridCell {
-                            cell_type: CellType::STEP,
-                            row: row_index,
-                            col: col_index,
+                        if guard.is_some() {
+                            return Err(ParseError::new(row_index + 1, "found a second guard"));
                         }
+                        guard = Some(Guard::new(row_index, col_index));
+                        CellType::STEP
                     }
-                    _ => panic!("Unknown character in grid"),
+                    _ => {
+                        return Err(ParseError::new(
+                            row_index + 1,
+                            format!("unknown character `{cell}` in grid"),
+                        ))
+                    }
+                };
+                Ok(GridCell {
+                    cell_type,
+                    row: row_index,
+                    col: col_index,
                 })
-                .collect();
-            GridRow::new(grid_row)
-        })
-        .collect();
+            })
+            .collect::<Result<Vec<GridCell>, _>>()?;
 
-    Grid { rows, guard }
-}
-
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut grid = parse(input);
-
-    while !grid.guard.is_at_exit(&grid.rows) {
-        grid.update_grid();
+        if rows
+            .first()
+            .is_some_and(|first| first.cells.len() != grid_row.len())
+        {
+            return Err(ParseError::new(row_index + 1, "rows differ in length"));
+        }
+        rows.push(GridRow::new(grid_row));
     }
 
-    let total_steps = grid.rows.iter().fold(0, |acc, row| {
-        acc + row
-            .cells
-            .iter()
-            .filter(|cell| cell.cell_type == CellType::STEP)
-            .count()
-    });
+    let guard = guard.ok_or_else(|| ParseError::new(0, "no guard found in grid"))?;
 
-    Some(total_steps + 1)
+    Ok(Grid { rows, guard })
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut grid = parse(input);
-    let starting_guard_position = (grid.guard.row, grid.guard.col);
-
-    while !grid.guard.is_at_exit(&grid.rows) {
-        grid.update_grid();
+/// Records the guard's walk, taking a snapshot at the start, after every turn and at the exit.
+pub fn export_frames(input: &str) -> Option<Frames<char>> {
+    let grid = parse(input).ok()?;
+    let palette = Palette::new(Rgb(250, 250, 250))
+        .with('#', Rgb(40, 40, 40))
+        .with('X', Rgb(120, 180, 240))
+        .with('^', Rgb(220, 50, 50));
+
+    let mut frames = Frames::new(palette);
+    let mut trail = grid.snapshot();
+    let snapshot = |trail: &grid::Grid<char>, position: Position| {
+        let mut frame = trail.clone();
+        frame.set(position, '^');
+        frame
+    };
+
+    frames.push(trail.clone());
+
+    let mut last = (grid.guard.row, grid.guard.col);
+    for (position, _, turned) in GuardWalk::new(&grid) {
+        if turned {
+            frames.push(snapshot(&trail, position));
+        }
+        trail.set(position, 'X');
+        last = position;
     }
+    frames.push(snapshot(&trail, last));
 
-    let mut step_cells: Vec<GridCell> = grid
-        .rows
-        .iter()
-        .flat_map(|row| {
-            row.cells
-                .iter()
-                .filter(|cell| {
-                    cell.cell_type == CellType::STEP
-                        && (cell.row != starting_guard_position.0
-                            || cell.col != starting_guard_position.1)
-                })
-                .copied()
-                .collect::<Vec<GridCell>>()
-        })
-        .collect();
+    Some(frames)
+}
 
-    step_cells.push(GridCell {
-        cell_type: CellType::STEP,
-        row: grid.guard.row,
-        col: grid.guard.col,
-    });
+pub fn part_one(input: &str) -> Option<usize> {
+    let grid = parse(input).ok()?;
 
-    let mut valid_new_obstacles = 0;
+    let visited: HashSet<Position> = GuardWalk::new(&grid)
+        .map(|(position, _, _)| position)
+        .collect();
 
-    for step in step_cells {
-        let mut grid = parse(input);
+    Some(visited.len())
+}
 
-        grid.rows[step.row].cells[step.col] = GridCell {
-            cell_type: CellType::OBSTACLE,
-            row: step.row,
-            col: step.col,
-        };
+pub fn part_two(input: &str) -> Option<usize> {
+    let grid = parse(input).ok()?;
+    let jumps = Jumps::new(&grid);
 
-        let mut total_steps = 0;
-        let grid_length = grid.rows.len();
-        let max_steps = grid_length * grid_length;
+    // an obstacle only changes the walk if it is placed on the original route, and only from the
+    // step the guard would first enter its cell on.
+    let mut tried = HashSet::from([(grid.guard.row, grid.guard.col)]);
+    let mut previous: Option<State> = None;
+    let mut valid_new_obstacles = 0;
 
-        'guard_loop: while !grid.guard.is_at_exit(&grid.rows) {
-            grid.update_grid();
-            total_steps += 1;
-            if total_steps >= max_steps {
+    for (position, direction, _) in GuardWalk::new(&grid) {
+        if let Some(previous) = previous {
+            if tried.insert(position) && jumps.walk(previous, Some(position)).1.is_some() {
                 valid_new_obstacles += 1;
-                break 'guard_loop;
             }
         }
+        previous = Some((position, direction));
     }
 
     Some(valid_new_obstacles)
 }
 
+/// Generates a map of `size + 2` square cells with scattered obstacles, at least 3 so that the
+/// guard fits inside the border. Like the real inputs, the guard is guaranteed to leave the map:
+/// layouts that trap the guard are discarded.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let side = size.max(1) + 2;
+
+    loop {
+        let guard = (rng.usize(1..side - 1), rng.usize(1..side - 1));
+        let input: String = (0..side)
+            .map(|row| {
+                let mut line: String = (0..side)
+                    .map(|col| match ((row, col) == guard, rng.u8(0..10)) {
+                        (true, _) => '^',
+                        (false, 0) => '#',
+                        (false, _) => '.',
+                    })
+                    .collect();
+                line.push('\n');
+                line
+            })
+            .collect();
+
+        let grid = parse(&input).unwrap();
+        if GuardWalk::new(&grid).finish() == Outcome::Exited {
+            return input;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -267,9 +499,152 @@ mod tests {
         assert_eq!(result, Some(41));
     }
 
+    #[test]
+    fn test_generate_small_maps() {
+        for size in 0..3 {
+            let grid = parse(&generate(size, 7)).unwrap();
+            assert_eq!(grid.width(), size.max(1) + 2);
+            assert_eq!(
+                part_one(&generate(size, 7)).map(|visited| visited > 0),
+                Some(true)
+            );
+        }
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn test_export_frames() {
+        let frames = export_frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let last = frames.frames.last().unwrap();
+        assert_eq!(frames.frames.len(), 12);
+        assert_eq!(
+            last.cells
+                .iter()
+                .filter(|cell| **cell != '.' && **cell != '#')
+                .count(),
+            41
+        );
+    }
+
+    #[test]
+    fn test_loop_path() {
+        let grid = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let path = grid.loop_path((6, 3)).unwrap();
+        assert_eq!(path.first(), Some(&(6, 4)));
+        assert_eq!(path.len(), 18);
+        assert_eq!(grid.loop_path((0, 0)), None);
+    }
+
+    #[test]
+    fn test_guard_walk() {
+        let grid = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        let mut walk = GuardWalk::new(&grid);
+        assert_eq!(walk.next(), Some(((6, 4), Direction::UP, false)));
+        assert_eq!(walk.by_ref().filter(|(_, _, turned)| *turned).count(), 10);
+        assert_eq!(walk.outcome(), Some(Outcome::Exited));
+
+        assert_eq!(
+            GuardWalk::with_obstacle(&grid, (6, 3)).finish(),
+            Outcome::Looped {
+                cycle_start: 0,
+                cycle_len: 22
+            }
+        );
+    }
+
+    #[test]
+    fn test_part_two_on_wide_maps() {
+        // the route is longer than the height squared, which is no reason to assume a loop.
+        let input = ".#.................\n..................#\n.^.................\n";
+        assert_eq!(part_two(input), Some(0));
+    }
+
+    /// Counts the obstacles that trap the guard by trying every empty cell and walking one cell
+    /// at a time, declaring a loop once a (position, direction) state repeats.
+    fn reference_part_two(rows: &[String]) -> Option<usize> {
+        let cells: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let (height, width) = (cells.len(), cells[0].len());
+        let start = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .find(|&(row, col)| cells[row][col] == '^')?;
+
+        let loops = |obstacle: Option<Position>| {
+            let mut seen = HashSet::new();
+            let (mut position, mut direction) = (start, Direction::UP);
+            while seen.insert((position, direction)) {
+                let Some(next) = direction.step(position, width, height) else {
+                    return false;
+                };
+                if cells[next.0][next.1] == '#' || Some(next) == obstacle {
+                    direction = direction.turn_right();
+                } else {
+                    position = next;
+                }
+            }
+            true
+        };
+
+        if loops(None) {
+            return None;
+        }
+        let obstacles = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .filter(|&(row, col)| cells[row][col] == '.')
+            .filter(|&obstacle| loops(Some(obstacle)))
+            .count();
+        Some(obstacles)
+    }
+
+    /// Generates rectangular maps of empty cells and obstacles with a single guard.
+    fn maps() -> impl Strategy<Value = Vec<String>> {
+        (1..12_usize, 1..12_usize)
+            .prop_flat_map(|(width, height)| {
+                (
+                    prop::collection::vec(prop::bool::weighted(0.2), width * height),
+                    0..width * height,
+                    Just(width),
+                )
+            })
+            .prop_map(|(obstacles, guard, width)| {
+                let cells: Vec<char> = obstacles
+                    .iter()
+                    .enumerate()
+                    .map(|(index, obstacle)| match (index == guard, obstacle) {
+                        (true, _) => '^',
+                        (false, true) => '#',
+                        (false, false) => '.',
+                    })
+                    .collect();
+                cells
+                    .chunks(width)
+                    .map(|row| row.iter().collect())
+                    .collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[.#^\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(rows in maps()) {
+            let grid = parse(&rows.join("\n")).unwrap();
+            prop_assert_eq!(grid.snapshot().to_string(), rows.join("\n"));
+        }
+
+        #[test]
+        fn test_part_two_matches_reference(rows in maps()) {
+            let expected = reference_part_two(&rows);
+            prop_assume!(expected.is_some());
+            prop_assert_eq!(part_two(&rows.join("\n")), expected);
+        }
+    }
 }
@@ -1,4 +1,9 @@
-advent_of_code::solution!(7);
+use advent_of_code::parse::{parse_number, ParseError};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::ops::ControlFlow;
+
+advent_of_code::solution!(7, parse = parse, generate = generate);
 
 pub struct Equation {
     pub result: u64,
@@ -11,162 +16,321 @@ impl Equation {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum Operator {
-    Add,
-    Multiply,
-    Concatenate,
+/// What an [`Operator`] can tell about its left operand from its result and right operand.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Inverse {
+    /// No left operand leads to the result.
+    Impossible,
+    /// Exactly this left operand leads to the result.
+    Unique(u64),
+    /// Several left operands may lead to the result, so they have to be evaluated forwards.
+    Ambiguous,
+}
+
+/// A binary operator combining the running result with the next value, evaluated left to right.
+#[derive(Clone, Copy, Debug)]
+pub struct Operator {
+    pub symbol: &'static str,
+    /// Combines the running result with the next value, [`None`] if that overflows.
+    pub apply: fn(u64, u64) -> Option<u64>,
+    /// Recovers the running result from the combined result and the next value, which lets the
+    /// solver work backwards from the target. Operators without one are only evaluated forwards.
+    pub invert: Option<fn(u64, u64) -> Inverse>,
+}
+
+impl PartialEq for Operator {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
 }
 
 impl Operator {
-    fn as_str(&self) -> &'static str {
-        match self {
-            Operator::Add => "+",
-            Operator::Multiply => "*",
-            Operator::Concatenate => "||",
-        }
+    pub const ADD: Operator = Operator {
+        symbol: "+",
+        apply: u64::checked_add,
+        invert: Some(subtract),
+    };
+
+    pub const MULTIPLY: Operator = Operator {
+        symbol: "*",
+        apply: u64::checked_mul,
+        invert: Some(divide),
+    };
+
+    pub const CONCATENATE: Operator = Operator {
+        symbol: "||",
+        apply: concatenate,
+        invert: Some(split_digits),
+    };
+}
+
+pub const PART_ONE_OPERATORS: &[Operator] = &[Operator::ADD, Operator::MULTIPLY];
+pub const PART_TWO_OPERATORS: &[Operator] =
+    &[Operator::ADD, Operator::MULTIPLY, Operator::CONCATENATE];
+
+fn subtract(result: u64, value: u64) -> Inverse {
+    result
+        .checked_sub(value)
+        .map_or(Inverse::Impossible, Inverse::Unique)
+}
+
+fn divide(result: u64, value: u64) -> Inverse {
+    match value {
+        0 if result == 0 => Inverse::Ambiguous,
+        _ if value != 0 && result.is_multiple_of(value) => Inverse::Unique(result / value),
+        _ => Inverse::Impossible,
     }
 }
 
-#[derive(Debug)]
-pub struct Solution {
-    pub expression: String,
-    pub result: u64,
+/// The power of ten that shifts a number left by the digits of `value`.
+fn digit_shift(value: u64) -> Option<u64> {
+    10_u64.checked_pow(value.checked_ilog10().unwrap_or(0) + 1)
 }
 
-pub fn evaluate(numbers: &[u64], operators: &[Operator]) -> u64 {
-    let mut result = numbers[0];
+fn concatenate(left: u64, right: u64) -> Option<u64> {
+    left.checked_mul(digit_shift(right)?)?.checked_add(right)
+}
 
-    for (i, op) in operators.iter().enumerate() {
-        match op {
-            Operator::Add => result += numbers[i + 1],
-            Operator::Multiply => result *= numbers[i + 1],
-            Operator::Concatenate => {
-                result = format!("{}{}", result, numbers[i + 1])
-                    .parse::<u64>()
-                    .unwrap()
-            }
-        }
+fn split_digits(result: u64, value: u64) -> Inverse {
+    match digit_shift(value) {
+        Some(shift) if result % shift == value => Inverse::Unique(result / shift),
+        _ => Inverse::Impossible,
     }
+}
 
-    result
+#[derive(Debug)]
+pub struct Solution {
+    /// Every way to make the equation true, e.g. `11 + 6 * 16 + 20`.
+    pub expressions: Vec<String>,
+    pub result: u64,
+}
+
+/// Evaluates `numbers` from left to right, [`None`] if any intermediate result overflows.
+pub fn evaluate(numbers: &[u64], operators: &[Operator]) -> Option<u64> {
+    numbers[1..]
+        .iter()
+        .zip(operators)
+        .try_fold(numbers[0], |result, (&number, operator)| {
+            (operator.apply)(result, number)
+        })
 }
 
 pub fn build_expression(numbers: &[u64], operators: &[Operator]) -> String {
     let mut expression = numbers[0].to_string();
 
     for (i, op) in operators.iter().enumerate() {
-        expression.push_str(&format!(" {} {}", op.as_str(), numbers[i + 1]));
+        expression.push_str(&format!(" {} {}", op.symbol, numbers[i + 1]));
     }
 
     expression
 }
 
-pub fn generate_expressions(
-    numbers: &[u64],
-    target: u64,
-    pos: usize,
-    current_ops: &mut Vec<Operator>,
-    part_two: bool,
-) -> Option<Solution> {
-    // Base case: we've placed all operators
-    if pos == numbers.len() - 1 {
-        let result = evaluate(numbers, current_ops);
-        if result == target {
-            return Some(Solution {
-                expression: build_expression(numbers, current_ops),
-                result,
-            });
-        }
-        return None;
+/// Finds the operators that make equations true, for a given set of operators.
+pub struct Solver<'a> {
+    operators: &'a [Operator],
+}
+
+/// Called with each combination of operators that makes the equation true.
+type Visit<'v> = &'v mut dyn FnMut(&[Operator]) -> ControlFlow<()>;
+/// Called with each result of evaluating forwards and the operators that produced it.
+type VisitResult<'v> = &'v mut dyn FnMut(u64, &[Operator]) -> ControlFlow<()>;
+
+impl<'a> Solver<'a> {
+    pub fn new(operators: &'a [Operator]) -> Self {
+        Solver { operators }
     }
 
-    // Try addition
-    current_ops.push(Operator::Add);
-    if let Some(solution) = generate_expressions(numbers, target, pos + 1, current_ops, part_two) {
-        return Some(solution);
+    /// Whether any combination of operators makes the equation true, stopping at the first.
+    pub fn is_solvable(&self, equation: &Equation) -> bool {
+        self.search(equation, &mut |_| ControlFlow::Break(()))
+            .is_break()
     }
-    current_ops.pop();
 
-    // Try multiplication
-    current_ops.push(Operator::Multiply);
-    if let Some(solution) = generate_expressions(numbers, target, pos + 1, current_ops, part_two) {
-        return Some(solution);
+    /// Every combination of operators that makes the equation true.
+    pub fn solutions(&self, equation: &Equation) -> Vec<Vec<Operator>> {
+        let mut solutions = vec![];
+        let _ = self.search(equation, &mut |operators| {
+            solutions.push(operators.to_vec());
+            ControlFlow::Continue(())
+        });
+        solutions
     }
-    current_ops.pop();
 
-    if part_two {
-        // Try concatenation
-        current_ops.push(Operator::Concatenate);
-        if let Some(solution) =
-            generate_expressions(numbers, target, pos + 1, current_ops, part_two)
-        {
-            return Some(solution);
+    fn search(&self, equation: &Equation, visit: Visit) -> ControlFlow<()> {
+        self.backward(equation.result, &equation.values, &mut vec![], visit)
+    }
+
+    /// Picks operators from the last value to the first, undoing each one from the target, so
+    /// that branches which cannot reach the target are cut early. `chosen` holds the operators
+    /// picked so far, last one first.
+    fn backward(
+        &self,
+        target: u64,
+        values: &[u64],
+        chosen: &mut Vec<Operator>,
+        visit: Visit,
+    ) -> ControlFlow<()> {
+        let Some((&last, rest)) = values.split_last() else {
+            return ControlFlow::Continue(());
+        };
+        if rest.is_empty() {
+            if last != target {
+                return ControlFlow::Continue(());
+            }
+            let operators: Vec<Operator> = chosen.iter().rev().copied().collect();
+            return visit(&operators);
         }
-        current_ops.pop();
+
+        for &operator in self.operators {
+            let inverse = operator.invert.map(|invert| invert(target, last));
+            let flow = match inverse {
+                Some(Inverse::Impossible) => ControlFlow::Continue(()),
+                Some(Inverse::Unique(left)) => {
+                    chosen.push(operator);
+                    let flow = self.backward(left, rest, chosen, visit);
+                    chosen.pop();
+                    flow
+                }
+                Some(Inverse::Ambiguous) | None => {
+                    self.forward(rest[0], &rest[1..], &mut vec![], &mut |value, prefix| {
+                        if (operator.apply)(value, last) != Some(target) {
+                            return ControlFlow::Continue(());
+                        }
+                        let operators: Vec<Operator> = prefix
+                            .iter()
+                            .copied()
+                            .chain([operator])
+                            .chain(chosen.iter().rev().copied())
+                            .collect();
+                        visit(&operators)
+                    })
+                }
+            };
+            flow?;
+        }
+
+        ControlFlow::Continue(())
     }
 
-    None
+    /// Evaluates `values` onto `result` with every combination of operators, skipping those that
+    /// overflow.
+    fn forward(
+        &self,
+        result: u64,
+        values: &[u64],
+        chosen: &mut Vec<Operator>,
+        visit: VisitResult,
+    ) -> ControlFlow<()> {
+        let Some((&next, rest)) = values.split_first() else {
+            return visit(result, chosen);
+        };
+
+        for &operator in self.operators {
+            if let Some(result) = (operator.apply)(result, next) {
+                chosen.push(operator);
+                let flow = self.forward(result, rest, chosen, visit);
+                chosen.pop();
+                flow?;
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
 }
 
-pub fn find_expression(equation: &Equation, part_two: bool) -> Option<Solution> {
-    let mut current_ops = Vec::new();
-    generate_expressions(
-        &equation.values,
-        equation.result,
-        0,
-        &mut current_ops,
-        part_two,
-    )
+/// Lists every expression that makes the equation true with the given operators.
+pub fn find_expressions(equation: &Equation, operators: &[Operator]) -> Option<Solution> {
+    let expressions: Vec<String> = Solver::new(operators)
+        .solutions(equation)
+        .iter()
+        .map(|chosen| build_expression(&equation.values, chosen))
+        .collect();
+
+    (!expressions.is_empty()).then_some(Solution {
+        expressions,
+        result: equation.result,
+    })
 }
 
-pub fn parse(input: &str) -> Vec<Equation> {
-    let equations = input
+pub fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+    input
         .split('\n')
-        .filter(|equation| !equation.is_empty())
-        .map(|equation| {
-            let parts: Vec<&str> = equation.split(": ").collect();
-            let result = parts[0].parse::<u64>().unwrap();
-            let values: Vec<u64> = parts[1]
+        .enumerate()
+        .filter(|(_, equation)| !equation.trim().is_empty())
+        .map(|(line_index, equation)| {
+            let line = line_index + 1;
+            let (result, values) = equation
+                .split_once(':')
+                .ok_or_else(|| ParseError::new(line, "expected `<result>: <values>`"))?;
+            let values: Vec<u64> = values
                 .split_ascii_whitespace()
-                .map(|value| value.parse::<u64>().unwrap())
-                .collect();
-            Equation::new(result, values)
+                .map(|value| parse_number(value, line))
+                .collect::<Result<_, _>>()?;
+            if values.is_empty() {
+                return Err(ParseError::new(line, "expected at least one value"));
+            }
+            Ok(Equation::new(parse_number(result, line)?, values))
         })
-        .collect();
+        .collect()
+}
+
+/// Sums the results of the equations that can be made true with the given operators. With the
+/// `parallel` feature, the equations are spread across threads.
+fn total_calibration_result(equations: &[Equation], operators: &[Operator]) -> u64 {
+    let solver = Solver::new(operators);
+    let result = |equation: &Equation| {
+        if solver.is_solvable(equation) {
+            equation.result
+        } else {
+            0
+        }
+    };
 
-    equations
+    #[cfg(feature = "parallel")]
+    return equations.par_iter().map(result).sum();
+
+    #[cfg(not(feature = "parallel"))]
+    equations.iter().map(result).sum()
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let equations = parse(input);
-
-    let total_results = equations
-        .iter()
-        .map(|equation| find_expression(equation, false))
-        .filter(|solution| solution.is_some())
-        .map(|solution| solution.unwrap().result)
-        .sum::<u64>();
+    let equations = parse(input).ok()?;
 
-    Some(total_results)
+    Some(total_calibration_result(&equations, PART_ONE_OPERATORS))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let equations = parse(input);
+    let equations = parse(input).ok()?;
 
-    let total_results = equations
-        .iter()
-        .map(|equation| find_expression(equation, true))
-        .filter(|solution| solution.is_some())
-        .map(|solution| solution.unwrap().result)
-        .sum::<u64>();
+    Some(total_calibration_result(&equations, PART_TWO_OPERATORS))
+}
+
+/// Generates `size` equations of two to eight values, roughly half of which can be made true.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
 
-    Some(total_results)
+    (0..size)
+        .map(|_| {
+            let values: Vec<u64> = (0..rng.usize(2..=8)).map(|_| rng.u64(1..100)).collect();
+            let result = if rng.bool() {
+                let chosen: Vec<Operator> = (1..values.len())
+                    .map(|_| PART_TWO_OPERATORS[rng.usize(..PART_TWO_OPERATORS.len())])
+                    .collect();
+                // NOTE: up to eight values below 100 cannot overflow.
+                evaluate(&values, &chosen).unwrap()
+            } else {
+                rng.u64(1..1_000_000)
+            };
+            let values: Vec<String> = values.iter().map(u64::to_string).collect();
+            format!("{result}: {}\n", values.join(" "))
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -179,4 +343,133 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_find_expressions() {
+        let solution =
+            find_expressions(&Equation::new(292, vec![11, 6, 16, 20]), PART_ONE_OPERATORS);
+        assert_eq!(solution.unwrap().expressions, vec!["11 + 6 * 16 + 20"]);
+
+        let solution = find_expressions(&Equation::new(4, vec![2, 2]), PART_TWO_OPERATORS);
+        assert_eq!(solution.unwrap().expressions, vec!["2 + 2", "2 * 2"]);
+
+        let solution =
+            find_expressions(&Equation::new(7290, vec![6, 8, 6, 15]), PART_TWO_OPERATORS);
+        assert_eq!(solution.unwrap().expressions, vec!["6 * 8 || 6 * 15"]);
+
+        assert!(find_expressions(&Equation::new(83, vec![17, 5]), PART_TWO_OPERATORS).is_none());
+    }
+
+    #[test]
+    fn test_skips_overflowing_expressions() {
+        let equation = Equation::new(0, vec![10_000_000_000, 10_000_000_000, 0]);
+        let solution = find_expressions(&equation, PART_TWO_OPERATORS).unwrap();
+        assert_eq!(solution.expressions, vec!["10000000000 + 10000000000 * 0"]);
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        // subtraction has no inverse registered, so it is only evaluated forwards.
+        let subtract = Operator {
+            symbol: "-",
+            apply: u64::checked_sub,
+            invert: None,
+        };
+        let operators = [Operator::ADD, subtract];
+        let solver = Solver::new(&operators);
+        let solutions = solver.solutions(&Equation::new(5, vec![10, 3, 2]));
+        assert_eq!(solutions, vec![vec![subtract, subtract]]);
+    }
+
+    /// Generates equations whose result is either reachable with random operators or random.
+    fn equations() -> impl Strategy<Value = Vec<(u64, Vec<u64>)>> {
+        let equation = prop::collection::vec(1..1000_u64, 1..6).prop_flat_map(|values| {
+            let operators =
+                prop::collection::vec(prop::sample::select(PART_TWO_OPERATORS), values.len() - 1);
+            (Just(values), operators, prop::option::of(1..100_000_u64)).prop_map(
+                |(values, operators, random_result)| {
+                    let result = random_result
+                        .unwrap_or_else(|| evaluate(&values, &operators).unwrap_or_default());
+                    (result, values)
+                },
+            )
+        });
+        prop::collection::vec(equation, 0..10)
+    }
+
+    fn to_input(equations: &[(u64, Vec<u64>)]) -> String {
+        equations
+            .iter()
+            .map(|(result, values)| {
+                let values: Vec<String> = values.iter().map(u64::to_string).collect();
+                format!("{result}: {}\n", values.join(" "))
+            })
+            .collect()
+    }
+
+    /// Counts the operator combinations that make an equation true by counting in base 2 or 3.
+    fn reference_solutions(result: u64, values: &[u64], part_two: bool) -> usize {
+        let base: u32 = if part_two { 3 } else { 2 };
+        let slots = values.len() as u32 - 1;
+        (0..base.pow(slots))
+            .filter(|&mut_combination| {
+                let mut combination = mut_combination;
+                let operators: Vec<Operator> = (0..slots)
+                    .map(|_| {
+                        let operator = PART_TWO_OPERATORS[(combination % base) as usize];
+                        combination /= base;
+                        operator
+                    })
+                    .collect();
+                evaluate(values, &operators) == Some(result)
+            })
+            .count()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9: \n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(equations in equations()) {
+            let parsed: Vec<(u64, Vec<u64>)> = parse(&to_input(&equations))
+                .unwrap()
+                .into_iter()
+                .map(|equation| (equation.result, equation.values))
+                .collect();
+            prop_assert_eq!(parsed, equations);
+        }
+
+        #[test]
+        fn test_parts_match_reference(equations in equations()) {
+            let expected = |part_two: bool| {
+                equations
+                    .iter()
+                    .filter(|(result, values)| reference_solutions(*result, values, part_two) > 0)
+                    .map(|(result, _)| result)
+                    .sum::<u64>()
+            };
+            prop_assert_eq!(part_one(&to_input(&equations)), Some(expected(false)));
+            prop_assert_eq!(part_two(&to_input(&equations)), Some(expected(true)));
+        }
+
+        #[test]
+        fn test_solutions_match_reference(equations in equations()) {
+            for (result, values) in equations {
+                let equation = Equation::new(result, values);
+                for (operators, part_two) in [(PART_ONE_OPERATORS, false), (PART_TWO_OPERATORS, true)] {
+                    let solutions = Solver::new(operators).solutions(&equation);
+                    prop_assert_eq!(
+                        solutions.len(),
+                        reference_solutions(equation.result, &equation.values, part_two)
+                    );
+                    for solution in solutions {
+                        prop_assert_eq!(evaluate(&equation.values, &solution), Some(equation.result));
+                    }
+                }
+            }
+        }
+    }
 }
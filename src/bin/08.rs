@@ -1,6 +1,8 @@
-use std::collections::HashSet;
+use advent_of_code::grid::Position;
+use advent_of_code::parse::ParseError;
+use std::{collections::HashSet, ops::RangeInclusive};
 
-advent_of_code::solution!(8);
+advent_of_code::solution!(8, parse = parse, generate = generate);
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct Node {
@@ -18,154 +20,133 @@ impl Node {
         }
     }
 
-    pub fn get_antinodes(&self, other: &Node, grid_size: i32, part1: bool) -> Vec<Node> {
+    /// The antinodes of this antenna and `other` on a `width` by `height` map: the positions `k`
+    /// times their distance beyond either antenna, for every `k` in `harmonics`. `1..=1` only
+    /// yields the nearest antinodes, `0..=usize::MAX` every one in line, the antennas included.
+    pub fn get_antinodes(
+        &self,
+        other: &Node,
+        width: usize,
+        height: usize,
+        harmonics: RangeInclusive<usize>,
+    ) -> Vec<Position> {
         let mut antinodes = Vec::new();
-        let dx = self.row as i32 - other.row as i32;
-        let dy = self.col as i32 - other.col as i32;
-
-        let add_antinodes = |start_row: i32, start_col: i32, dx: i32, dy: i32| {
-            let mut antinode_row = start_row;
-            let mut antinode_col = start_col;
-
-            let mut local_antinodes = Vec::new();
-            loop {
-                antinode_row += dx;
-                antinode_col += dy;
-
-                if antinode_row < 0
-                    || antinode_row >= grid_size
-                    || antinode_col < 0
-                    || antinode_col >= grid_size
-                {
-                    break;
-                }
 
-                local_antinodes.push(Node::new(
-                    "#".to_string(),
-                    antinode_row as usize,
-                    antinode_col as usize,
-                ));
+        for (from, to) in [(self, other), (other, self)] {
+            let d_row = from.row as isize - to.row as isize;
+            let d_col = from.col as isize - to.col as isize;
+            if (d_row, d_col) == (0, 0) {
+                continue;
             }
 
-            local_antinodes
-        };
-
-        let mut first_half = add_antinodes(self.row as i32, self.col as i32, dx, dy);
-        let mut second_half = add_antinodes(other.row as i32, other.col as i32, -dx, -dy);
-
-        if part1 {
-            if !first_half.is_empty() {
-                antinodes.push(first_half[0].clone());
-            }
-            if !second_half.is_empty() {
-                antinodes.push(second_half[0].clone());
+            for k in harmonics.clone() {
+                let Some(antinode) = isize::try_from(k).ok().and_then(|k| {
+                    let row = from.row.checked_add_signed(d_row.checked_mul(k)?)?;
+                    let col = from.col.checked_add_signed(d_col.checked_mul(k)?)?;
+                    (row < height && col < width).then_some((row, col))
+                }) else {
+                    break;
+                };
+                antinodes.push(antinode);
             }
-        } else {
-            antinodes.append(&mut first_half);
-            antinodes.append(&mut second_half);
         }
+
         antinodes
     }
 }
 
-pub fn parse(input: &str) -> (i32, Vec<Node>) {
-    let grid_size = input.lines().count() as i32;
-    let nodes = input
+/// The antennas on a `width` by `height` map.
+pub struct AntennaMap {
+    pub width: usize,
+    pub height: usize,
+    pub nodes: Vec<Node>,
+}
+
+pub fn parse(input: &str) -> Result<AntennaMap, ParseError> {
+    let rows: Vec<&str> = input
         .split('\n')
+        .map(str::trim_end)
         .filter(|row| !row.is_empty())
+        .collect();
+
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if let Some(row_index) = rows.iter().position(|row| row.chars().count() != width) {
+        return Err(ParseError::new(row_index + 1, "rows differ in length"));
+    }
+
+    let nodes = rows
+        .iter()
         .enumerate()
-        .filter_map(|(row_index, row)| {
-            let nodes = row
-                .chars()
+        .flat_map(|(row_index, row)| {
+            row.chars()
                 .enumerate()
-                .filter_map(|(col_index, ch)| {
-                    if ch == '.' {
-                        None
-                    } else {
-                        let frequency = ch.to_string();
-                        Some(Node::new(frequency, row_index, col_index))
-                    }
-                })
-                .collect::<Vec<Node>>();
-            if !nodes.is_empty() {
-                Some(nodes)
-            } else {
-                None
-            }
+                .filter(|(_, ch)| *ch != '.')
+                .map(move |(col_index, ch)| Node::new(ch.to_string(), row_index, col_index))
         })
-        .flatten()
         .collect();
 
-    (grid_size, nodes)
+    Ok(AntennaMap {
+        width,
+        height: rows.len(),
+        nodes,
+    })
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let (grid_size, nodes) = parse(input);
-    let frequencies = nodes
-        .iter()
-        .map(|node| node.frequency.clone())
-        .collect::<Vec<String>>();
-
-    let mut antinodes = HashSet::new();
+/// Counts the distinct antinodes of every pair of antennas sharing a frequency.
+pub fn count_antinodes(map: &AntennaMap, harmonics: RangeInclusive<usize>) -> usize {
+    let mut antinodes: HashSet<Position> = HashSet::new();
 
-    frequencies.iter().for_each(|frequency| {
-        let current_nodes = nodes
+    for (index, node) in map.nodes.iter().enumerate() {
+        for other in map.nodes[index + 1..]
             .iter()
-            .filter(|node| node.frequency == *frequency)
-            .collect::<Vec<&Node>>();
-
-        for (index, node) in current_nodes.iter().enumerate() {
-            for other_node in &current_nodes[index + 1..] {
-                let new_antinodes = node.get_antinodes(other_node, grid_size, true);
-                for antinode in new_antinodes {
-                    antinodes.insert(antinode.clone());
-                }
-            }
+            .filter(|other| other.frequency == node.frequency)
+        {
+            antinodes.extend(node.get_antinodes(other, map.width, map.height, harmonics.clone()));
         }
-    });
-
-    antinodes.retain(|node| node.col != 99 && node.row != 99);
+    }
 
-    Some(antinodes.len())
+    antinodes.len()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let (grid_size, nodes) = parse(input);
-    let frequencies = nodes
-        .iter()
-        .map(|node| node.frequency.clone())
-        .collect::<Vec<String>>();
+pub fn part_one(input: &str) -> Option<usize> {
+    let map = parse(input).ok()?;
 
-    let mut antinodes = HashSet::new();
+    Some(count_antinodes(&map, 1..=1))
+}
 
-    frequencies.iter().for_each(|frequency| {
-        let current_nodes = nodes
-            .iter()
-            .filter(|node| node.frequency == *frequency)
-            .collect::<Vec<&Node>>();
-
-        for (index, node) in current_nodes.iter().enumerate() {
-            for other_node in &current_nodes[index + 1..] {
-                let new_antinodes = node.get_antinodes(other_node, grid_size, false);
-                for antinode in new_antinodes {
-                    antinodes.insert(antinode.clone());
-                }
-            }
-        }
-    });
+pub fn part_two(input: &str) -> Option<usize> {
+    let map = parse(input).ok()?;
 
-    for node in nodes {
-        antinodes.insert(Node::new("#".to_owned(), node.row, node.col));
-    }
+    // NOTE: antennas are antinodes themselves as long as another antenna shares their frequency.
+    Some(count_antinodes(&map, 0..=usize::MAX))
+}
 
-    antinodes.retain(|node| node.col != 99 && node.row != 99);
+/// Generates a `size` by `size` map with about one antenna per row, spread over a few frequencies.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let frequencies = ['a', 'b', 'A', 'B', '0', '1'];
 
-    Some(antinodes.len())
+    (0..size)
+        .map(|_| {
+            let mut row: String = (0..size)
+                .map(|_| {
+                    if rng.usize(..size) == 0 {
+                        frequencies[rng.usize(..frequencies.len())]
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -178,4 +159,118 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_part_two_ignores_lone_antennas() {
+        assert_eq!(part_two("a...\n....\n....\n...."), Some(0));
+        assert_eq!(part_two("...a\n.A..\n..A.\n...."), Some(4));
+    }
+
+    #[test]
+    fn test_wide_map() {
+        let input = "..........\n...a.a....\n..........\n";
+        assert_eq!(part_one(input), Some(2));
+        assert_eq!(part_two(input), Some(5));
+    }
+
+    #[test]
+    fn test_tall_map() {
+        let input = "...\n...\n...\n.a.\n...\n.a.\n...\n...\n...\n...\n";
+        assert_eq!(part_one(input), Some(2));
+        assert_eq!(part_two(input), Some(5));
+    }
+
+    #[test]
+    fn test_harmonic_range() {
+        let (a, b) = (Node::new("a".into(), 1, 3), Node::new("a".into(), 1, 5));
+        assert_eq!(a.get_antinodes(&b, 10, 3, 2..=2), vec![(1, 9)]);
+        assert_eq!(
+            a.get_antinodes(&b, 10, 3, 1..=2),
+            vec![(1, 1), (1, 7), (1, 9)]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_ragged_rows() {
+        assert_eq!(
+            parse("...\n..\n").err(),
+            Some(ParseError::new(2, "rows differ in length"))
+        );
+    }
+
+    /// Generates rectangular maps with a handful of antenna frequencies.
+    fn antenna_maps() -> impl Strategy<Value = Vec<String>> {
+        let cell = prop_oneof![8 => Just('.'), 1 => Just('a'), 1 => Just('A'), 1 => Just('0')];
+        (1..12_usize, 1..12_usize).prop_flat_map(move |(width, height)| {
+            prop::collection::vec(
+                prop::collection::vec(cell.clone(), width).prop_map(String::from_iter),
+                height,
+            )
+        })
+    }
+
+    fn reference_antinodes(rows: &[String], harmonics: bool) -> usize {
+        let antennas: Vec<(i32, i32, char)> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c != '.')
+                    .map(move |(col, c)| (row as i32, col as i32, c))
+            })
+            .collect();
+        let (width, height) = (rows[0].len() as i32, rows.len() as i32);
+        let mut count = 0;
+
+        for row in 0..height {
+            for col in 0..width {
+                let is_antinode = antennas.iter().any(|a| {
+                    antennas.iter().any(|b| {
+                        if a == b || a.2 != b.2 {
+                            return false;
+                        }
+                        let (d_row, d_col) = (a.0 - b.0, a.1 - b.1);
+                        let (p_row, p_col) = (row - a.0, col - a.1);
+                        if harmonics {
+                            // p - a must be an integer multiple of a - b.
+                            p_row * d_col == p_col * d_row
+                                && (d_row == 0 || p_row % d_row == 0)
+                                && (d_col == 0 || p_col % d_col == 0)
+                        } else {
+                            (p_row, p_col) == (d_row, d_col)
+                        }
+                    })
+                });
+                count += usize::from(is_antinode);
+            }
+        }
+
+        count
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[.aA0\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(rows in antenna_maps()) {
+            let map = parse(&rows.join("\n")).unwrap();
+            let mut rendered = vec![vec!['.'; map.width]; map.height];
+            for node in map.nodes {
+                rendered[node.row][node.col] = node.frequency.chars().next().unwrap();
+            }
+            let rendered: Vec<String> = rendered.iter().map(|row| row.iter().collect()).collect();
+            prop_assert_eq!(rendered, rows);
+        }
+
+        #[test]
+        fn test_parts_match_reference(rows in antenna_maps()) {
+            let input = rows.join("\n");
+            prop_assert_eq!(part_one(&input), Some(reference_antinodes(&rows, false)));
+            prop_assert_eq!(part_two(&input), Some(reference_antinodes(&rows, true)));
+        }
+    }
 }
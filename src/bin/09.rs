@@ -1,4 +1,17 @@
-advent_of_code::solution!(9);
+use advent_of_code::parse::{parse_number, ParseError};
+use std::{
+    cmp::Reverse,
+    collections::{btree_map::Entry, BTreeMap, BinaryHeap},
+    fmt::{self, Write},
+};
+
+advent_of_code::solution!(
+    9,
+    parse = parse,
+    reference_one = reference_part_one,
+    reference_two = reference_part_two,
+    generate = generate,
+);
 
 pub struct File {
     pub id: u32,
@@ -6,153 +19,328 @@ pub struct File {
     pub free_blocks: u32,
 }
 
-pub fn parse(input: &str) -> Vec<File> {
-    let files = input
-        .chars()
-        .collect::<Vec<char>>()
+/// Parses a disk map of alternating file and free space sizes. Sizes are either single digits,
+/// as in the puzzle, or separated by commas to allow sizes above 9. Whitespace is ignored.
+pub fn parse(input: &str) -> Result<Vec<File>, ParseError> {
+    let sizes = if input.contains(',') {
+        parse_separated_sizes(input)?
+    } else {
+        parse_digit_sizes(input)?
+    };
+
+    let files = sizes
         .chunks(2)
         .enumerate()
-        .map(|(index, file_chunk)| {
-            let size = file_chunk[0].to_digit(10).unwrap();
-            let mut free_blocks = 0;
-            if file_chunk.len() == 2 {
-                free_blocks = file_chunk[1].to_digit(10).unwrap();
-            }
-            File {
-                id: index as u32,
-                size,
-                free_blocks,
-            }
+        .map(|(index, file_chunk)| File {
+            id: index as u32,
+            size: file_chunk[0],
+            free_blocks: file_chunk.get(1).copied().unwrap_or(0),
         })
         .collect();
 
-    files
+    Ok(files)
 }
 
-pub fn create_filesystem(files: &[File]) -> Vec<String> {
-    let mut filesystem: Vec<String> = Vec::new();
+fn parse_digit_sizes(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut sizes = vec![];
 
-    for file in files {
-        let stringified_file = vec![file.id.to_string(); file.size as usize];
-        let stringified_free_space = vec![".".to_string(); file.free_blocks as usize];
-        filesystem.extend(stringified_file);
-        filesystem.extend(stringified_free_space);
+    for (line_index, line) in input.lines().enumerate() {
+        for (column, c) in line.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let size = c.to_digit(10).ok_or_else(|| {
+                ParseError::new(
+                    line_index + 1,
+                    format!("invalid block size `{c}` in column {}", column + 1),
+                )
+            })?;
+            sizes.push(size);
+        }
     }
 
-    filesystem
+    Ok(sizes)
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let files = parse(input);
-    let filesystem = create_filesystem(&files);
+/// Parses comma-separated sizes, which may span several lines and end with a trailing comma.
+fn parse_separated_sizes(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut sizes = vec![];
 
-    let free_positions: Vec<usize> = filesystem
-        .clone()
-        .iter()
-        .enumerate()
-        .filter_map(|(index, block)| if block == "." { Some(index) } else { None })
-        .collect();
+    for (line_index, line) in input.lines().enumerate() {
+        let values: Vec<&str> = line.split(',').map(str::trim).collect();
+        for (index, value) in values.iter().enumerate() {
+            if value.is_empty() {
+                if index + 1 == values.len() {
+                    continue;
+                }
+                return Err(ParseError::new(
+                    line_index + 1,
+                    "expected a block size between commas",
+                ));
+            }
+            sizes.push(parse_number(value, line_index + 1)?);
+        }
+    }
 
-    let mut file_blocks: Vec<String> = filesystem
-        .iter()
-        .filter(|block| *block != ".")
-        .cloned()
-        .collect();
+    Ok(sizes)
+}
+
+/// A file on the disk: `length` blocks starting at block `offset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub id: u32,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl Span {
+    /// The sum of each block's position multiplied by the file id.
+    pub fn checksum(&self) -> u64 {
+        let (offset, length) = (self.offset as u64, self.length as u64);
+        u64::from(self.id) * (length * offset + length * length.saturating_sub(1) / 2)
+    }
+}
+
+/// The layout of a disk of `size` blocks, with the spans taken by files. A compacted disk may
+/// hold several spans of the same file.
+#[derive(Clone, Debug)]
+pub struct Disk {
+    pub files: Vec<Span>,
+    pub size: usize,
+}
+
+impl Disk {
+    pub fn new(files: &[File]) -> Self {
+        let mut spans = Vec::with_capacity(files.len());
+        let mut offset = 0;
 
-    for free_position in free_positions {
-        if free_position > file_blocks.len() {
-            break;
+        for file in files {
+            let length = file.size as usize;
+            // NOTE: empty files have no blocks to move and do not count towards the checksum.
+            if length > 0 {
+                spans.push(Span {
+                    id: file.id,
+                    offset,
+                    length,
+                });
+            }
+            offset += length + file.free_blocks as usize;
         }
-        if let Some(last_block) = file_blocks.pop() {
-            file_blocks.insert(free_position, last_block);
+
+        Disk {
+            files: spans,
+            size: offset,
         }
     }
 
-    let checksum = file_blocks
-        .iter()
-        .enumerate()
-        .filter_map(|(index, block)| {
-            if block != "." {
-                Some(block.parse::<u64>().unwrap() * index as u64)
-            } else {
-                None
+    /// The spans of free blocks as `(offset, length)`, from left to right.
+    pub fn free_spans(&self) -> Vec<(usize, usize)> {
+        let mut files = self.files.clone();
+        files.sort_unstable_by_key(|file| file.offset);
+
+        let mut free_spans = vec![];
+        let mut offset = 0;
+        for file in files {
+            if file.offset > offset {
+                free_spans.push((offset, file.offset - offset));
             }
-        })
-        .sum();
+            offset = file.offset + file.length;
+        }
+        if self.size > offset {
+            free_spans.push((offset, self.size - offset));
+        }
+        free_spans
+    }
 
-    Some(checksum)
-}
+    /// Moves blocks one at a time from the end of the disk into the leftmost free block, until
+    /// no free space is left between files.
+    pub fn compact_blocks(&self) -> Disk {
+        let mut files = self.files.clone();
+        files.sort_unstable_by_key(|file| file.offset);
+        let mut moved = vec![];
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let files = parse(input);
-    let mut filesystem = create_filesystem(&files);
-
-    for file in files.iter().rev().take(files.len() - 1) {
-        let id_str = file.id.to_string();
-
-        // Find first sequence of dots that's large enough
-        let mut start_index = 0;
-        'outer: while start_index < filesystem.len() {
-            let mut space_size = 0;
-            let mut space_start = None;
-
-            // Count contiguous dots
-            for (i, block) in filesystem[start_index..].iter().enumerate() {
-                if block == "." {
-                    if space_start.is_none() {
-                        space_start = Some(start_index + i);
-                    }
-                    space_size += 1;
-                } else {
-                    if space_size >= file.size {
-                        break;
-                    }
-                    space_size = 0;
-                    space_start = None;
+        'free_spans: for (mut offset, mut length) in self.free_spans() {
+            while length > 0 {
+                let Some(last) = files.last_mut().filter(|last| last.offset > offset) else {
+                    break 'free_spans;
+                };
+
+                let blocks = length.min(last.length);
+                moved.push(Span {
+                    id: last.id,
+                    offset,
+                    length: blocks,
+                });
+                (offset, length) = (offset + blocks, length - blocks);
+
+                // the blocks are taken from the end of the file, so its offset stays the same.
+                last.length -= blocks;
+                if last.length == 0 {
+                    files.pop();
                 }
             }
-            // If we found a space large enough
-            if space_size >= file.size {
-                if let Some(space_start) = space_start {
-                    let original_position = filesystem
-                        .iter()
-                        .position(|block| *block == id_str)
-                        .unwrap();
-
-                    if space_start > original_position {
-                        break 'outer;
-                    }
-
-                    for i in 0..file.size {
-                        filesystem[space_start + i as usize] = id_str.clone();
-                        filesystem[original_position + i as usize] = ".".to_string();
-                    }
-                    break 'outer;
+        }
+
+        files.extend(moved);
+        Disk {
+            files,
+            size: self.size,
+        }
+    }
+
+    /// Moves whole files, highest id first, into the leftmost span of free blocks that fits them.
+    /// Free spans are kept in one min-heap of offsets per length that occurs, so the leftmost
+    /// fitting span is the smallest top among the heaps for lengths of at least the file's.
+    pub fn compact_files(&self) -> Disk {
+        let mut files = self.files.clone();
+        files.sort_unstable_by_key(|file| Reverse(file.id));
+
+        let mut free: BTreeMap<usize, BinaryHeap<Reverse<usize>>> = BTreeMap::new();
+        for (offset, length) in self.free_spans() {
+            free.entry(length).or_default().push(Reverse(offset));
+        }
+
+        for file in &mut files {
+            let leftmost = free
+                .range(file.length..)
+                .filter_map(|(&length, heap)| heap.peek().map(|&Reverse(offset)| (offset, length)))
+                .min();
+            let Some((offset, length)) = leftmost.filter(|(offset, _)| *offset < file.offset)
+            else {
+                continue;
+            };
+
+            if let Entry::Occupied(mut heap) = free.entry(length) {
+                heap.get_mut().pop();
+                if heap.get().is_empty() {
+                    heap.remove();
                 }
             }
+            file.offset = offset;
 
-            start_index += 1;
+            // space freed up by the file is never used, as only files to its left are left to move.
+            let rest = length - file.length;
+            if rest > 0 {
+                free.entry(rest)
+                    .or_default()
+                    .push(Reverse(offset + file.length));
+            }
         }
+
+        Disk {
+            files,
+            size: self.size,
+        }
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.files.iter().map(Span::checksum).sum()
+    }
+}
+
+/// Renders the disk like the puzzle does, with `.` for free blocks. Ids above 9 take up several
+/// characters per block.
+impl fmt::Display for Disk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blocks = vec![None; self.size];
+        for file in &self.files {
+            blocks[file.offset..file.offset + file.length].fill(Some(file.id));
+        }
+
+        for block in blocks {
+            match block {
+                Some(id) => write!(f, "{id}")?,
+                None => f.write_char('.')?,
+            }
+        }
+        Ok(())
     }
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let files = parse(input).ok()?;
+
+    Some(Disk::new(&files).compact_blocks().checksum())
+}
 
-    let checksum = filesystem
+pub fn part_two(input: &str) -> Option<u64> {
+    let files = parse(input).ok()?;
+
+    Some(Disk::new(&files).compact_files().checksum())
+}
+
+/// Expands the files into one entry per block, holding the file id or [`None`] for free space.
+fn to_blocks(files: &[File]) -> Vec<Option<u32>> {
+    files
+        .iter()
+        .flat_map(|file| {
+            std::iter::repeat_n(Some(file.id), file.size as usize)
+                .chain(std::iter::repeat_n(None, file.free_blocks as usize))
+        })
+        .collect()
+}
+
+fn block_checksum(blocks: &[Option<u32>]) -> u64 {
+    blocks
         .iter()
         .enumerate()
-        .filter_map(|(index, block)| {
-            if block != "." {
-                Some(block.parse::<u64>().unwrap() * index as u64)
-            } else {
-                None
+        .map(|(index, id)| id.map_or(0, |id| index as u64 * u64::from(id)))
+        .sum()
+}
+
+/// Moves single blocks from the end into the leftmost free block, used to cross-check [`part_one`].
+pub fn reference_part_one(input: &str) -> Option<u64> {
+    let mut blocks = to_blocks(&parse(input).ok()?);
+    let (mut left, mut right) = (0, blocks.len().saturating_sub(1));
+
+    while left < right {
+        match (blocks[left], blocks[right]) {
+            (Some(_), _) => left += 1,
+            (_, None) => right -= 1,
+            (None, Some(_)) => blocks.swap(left, right),
+        }
+    }
+
+    Some(block_checksum(&blocks))
+}
+
+/// Moves whole files, highest id first, into the leftmost span of free blocks that fits,
+/// used to cross-check [`part_two`].
+pub fn reference_part_two(input: &str) -> Option<u64> {
+    let files = parse(input).ok()?;
+    let mut blocks = to_blocks(&files);
+
+    for file in files.iter().rev() {
+        let Some(start) = blocks.iter().position(|block| *block == Some(file.id)) else {
+            continue;
+        };
+        let size = file.size as usize;
+        let target = (0..start).find(|&free| blocks[free..free + size].iter().all(Option::is_none));
+        if let Some(target) = target {
+            for offset in 0..size {
+                blocks.swap(target + offset, start + offset);
             }
-        })
-        .sum();
+        }
+    }
 
-    Some(checksum)
+    Some(block_checksum(&blocks))
+}
+
+/// Generates a disk map of `size` files with 1 to 9 blocks and 0 to 9 free blocks each.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let mut disk_map: String = (0..size.max(1))
+        .map(|_| format!("{}{}", rng.u32(1..=9), rng.u32(0..=9)))
+        .collect();
+    // NOTE: the last file is not followed by free space.
+    disk_map.pop();
+    disk_map
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -165,4 +353,150 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2858));
     }
+
+    #[test]
+    fn test_part_one_fills_free_space_up_to_the_last_block() {
+        // the second free block lies right behind the last file block once it is moved.
+        assert_eq!(part_one("1211"), Some(1));
+    }
+
+    #[test]
+    fn test_render_disk() {
+        let files = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let disk = Disk::new(&files);
+        assert_eq!(
+            disk.to_string(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert_eq!(
+            disk.compact_blocks().to_string(),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(
+            disk.compact_files().to_string(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_whitespace() {
+        let files = parse("12 3\n45\n").unwrap();
+        let sizes: Vec<(u32, u32)> = files
+            .iter()
+            .map(|file| (file.size, file.free_blocks))
+            .collect();
+        assert_eq!(sizes, vec![(1, 2), (3, 4), (5, 0)]);
+    }
+
+    #[test]
+    fn test_parse_reports_invalid_characters() {
+        assert_eq!(
+            parse("123\n4x5").err(),
+            Some(ParseError::new(2, "invalid block size `x` in column 2"))
+        );
+        assert_eq!(
+            parse("1,,2").err(),
+            Some(ParseError::new(1, "expected a block size between commas"))
+        );
+        assert_eq!(
+            parse("1, 2,\n3, x").err(),
+            Some(ParseError::new(2, "expected a number, found `x`"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_maps_are_reported() {
+        use advent_of_code::template::runner::Parsed;
+
+        // the runner prints this error, as the parts only return `None`.
+        assert_eq!(
+            parse("123\n4x5").error().as_deref(),
+            Some("line 2: invalid block size `x` in column 2")
+        );
+        assert_eq!(part_one("123\n4x5"), None);
+        assert_eq!(part_two("123\n4x5"), None);
+    }
+
+    #[test]
+    fn test_parse_separated_sizes() {
+        let files = parse("12, 0, 3,\n25\n").unwrap();
+        let sizes: Vec<(u32, u32)> = files
+            .iter()
+            .map(|file| (file.size, file.free_blocks))
+            .collect();
+        assert_eq!(sizes, vec![(12, 0), (3, 25)]);
+        assert_eq!(Disk::new(&files).compact_files().checksum(), 12 + 13 + 14);
+    }
+
+    #[test]
+    fn test_compact_files_with_long_spans() {
+        // file 1 moves into the first free block, file 2 does not fit anywhere.
+        let expected = 1 + 2 * (20_000_000 * 4 + 20_000_000 * 19_999_999 / 2);
+        assert_eq!(part_two("1,1,1,1,20000000"), Some(expected));
+        assert_eq!(part_two("1,20000000,1"), Some(1));
+    }
+
+    #[test]
+    fn test_separated_sizes_near_a_billion() {
+        let input = "1,1,1,1,1000000000";
+        let expected = 1 + 2 * (1_000_000_000 * 4 + 1_000_000_000 * 999_999_999 / 2);
+        assert_eq!(part_two(input), Some(expected));
+        assert_eq!(part_two("1, 1000000000,\n1"), Some(1));
+    }
+
+    /// Generates disk maps of files with 1 to 9 blocks separated by 0 to 9 free blocks.
+    fn disk_maps() -> impl Strategy<Value = Vec<(u32, u32)>> {
+        prop::collection::vec((1..=9_u32, 0..=9_u32), 1..30)
+    }
+
+    fn to_input(disk_map: &[(u32, u32)]) -> String {
+        disk_map
+            .iter()
+            .map(|(size, free_blocks)| format!("{size}{free_blocks}"))
+            .collect()
+    }
+
+    fn to_separated_input(disk_map: &[(u32, u32)]) -> String {
+        disk_map
+            .iter()
+            .map(|(size, free_blocks)| format!("{size}, {free_blocks},\n"))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9.\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(disk_map in disk_maps()) {
+            let files = parse(&to_input(&disk_map)).unwrap();
+            let parsed: Vec<(u32, u32)> = files.iter().map(|file| (file.size, file.free_blocks)).collect();
+            prop_assert!(files.iter().enumerate().all(|(index, file)| file.id as usize == index));
+            prop_assert_eq!(parsed, disk_map);
+        }
+
+        #[test]
+        fn test_parse_separated_round_trips(disk_map in prop::collection::vec((1..1000_u32, 0..1000_u32), 1..30)) {
+            let files = parse(&to_separated_input(&disk_map)).unwrap();
+            let parsed: Vec<(u32, u32)> = files.iter().map(|file| (file.size, file.free_blocks)).collect();
+            prop_assert_eq!(parsed, disk_map);
+        }
+
+        #[test]
+        fn test_formats_match(disk_map in disk_maps()) {
+            prop_assert_eq!(
+                part_two(&to_input(&disk_map)),
+                part_two(&to_separated_input(&disk_map))
+            );
+        }
+
+        #[test]
+        fn test_parts_match_reference(disk_map in disk_maps()) {
+            let input = to_input(&disk_map);
+            prop_assert_eq!(part_one(&input), reference_part_one(&input));
+            prop_assert_eq!(part_two(&input), reference_part_two(&input));
+        }
+    }
 }
@@ -1,101 +1,161 @@
+use advent_of_code::export::{Frames, Palette, Rgb};
+use advent_of_code::graph;
+use advent_of_code::grid::{Grid, Position};
+use advent_of_code::parse::ParseError;
 use pathfinding::prelude::{bfs_reach, count_paths};
+use std::collections::HashSet;
+
+advent_of_code::solution!(
+    10,
+    parse = parse,
+    export = export_frames,
+    generate = generate
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrailCell {
+    Height(i32),
+    Trail(i32),
+}
 
-advent_of_code::solution!(10);
+/// Places the parsed rows on a [`Grid`], so trails can be walked with the shared graph helpers.
+fn to_grid(grid_rows: &[Vec<i32>]) -> Grid<i32> {
+    let height = grid_rows.len();
+    let width = grid_rows.first().map_or(0, Vec::len);
+    Grid::new(width, height, grid_rows.concat())
+}
 
-pub fn count_reachable_nines(grid: &[Vec<i32>], part1: bool) -> usize {
-    // Find all starting positions (zeros)
-    let zeros: Vec<(usize, usize)> = grid
-        .iter()
-        .enumerate()
-        .flat_map(|(i, row)| {
-            row.iter()
-                .enumerate()
-                .filter(|(_, &val)| val == 0)
-                .map(move |(j, _)| (i, j))
-        })
-        .collect();
-
-    let grid_size = grid.len();
-
-    // Function to get valid next positions
-    let successors = |(x, y): &(usize, usize)| {
-        let curr_val = grid[*x][*y];
-        let target_val = curr_val + 1;
-
-        // Define possible moves (up, down, left, right)
-        [(0, 1), (1, 0), (0, -1), (-1, 0)]
-            .iter()
-            .filter_map(|&(dx, dy)| {
-                let new_x = (*x as i32 + dx) as usize;
-                let new_y = (*y as i32 + dy) as usize;
-
-                if new_x < grid_size && new_y < grid_size && grid[new_x][new_y] == target_val {
-                    Some((new_x, new_y))
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>()
-    };
+pub fn count_reachable_nines(grid_rows: &[Vec<i32>], part1: bool) -> usize {
+    let grid = to_grid(grid_rows);
+    // hiking trails climb by exactly one step of elevation per move.
+    let successors = graph::grid_successors(&grid, |from, to| *to == from + 1);
+    let is_summit = |pos: &Position| grid.get(*pos) == Some(&9);
+
+    let trailheads = grid.positions().filter(|&pos| grid.get(pos) == Some(&0));
 
     if part1 {
-        // Use bfs_reach to find all reachable positions from all zeros
-        let reachable_nines: usize = zeros
-            .iter()
-            .map(|&start| {
-                let reachable_nodes: Vec<_> = bfs_reach(start, &successors).collect();
-
-                reachable_nodes
-                    .into_iter()
-                    .filter(|&pos| grid[pos.0][pos.1] == 9)
-                    .count()
-            })
-            .sum();
-
-        reachable_nines
+        trailheads
+            .map(|start| bfs_reach(start, &successors).filter(is_summit).count())
+            .sum()
     } else {
-        // Success function to identify when we've reached a 9
-        let success = |&(x, y): &(usize, usize)| grid[x][y] == 9;
-
-        // Count paths from each 0 to any 9
-        zeros
-            .iter()
-            .map(|&start| count_paths(start, successors, success))
+        trailheads
+            .map(|start| count_paths(start, &successors, is_summit))
             .sum()
     }
 }
 
-pub fn parse(input: &str) -> Vec<Vec<i32>> {
-    let grid_rows = input
+pub fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    let grid_rows: Vec<Vec<i32>> = input
         .split('\n')
+        .map(str::trim_end)
         .filter(|row| !row.is_empty())
-        .map(|row| {
+        .enumerate()
+        .map(|(row_index, row)| {
             row.chars()
-                .map(|elevation| elevation.to_digit(10).unwrap() as i32)
+                .map(|elevation| {
+                    elevation
+                        .to_digit(10)
+                        .map(|elevation| elevation as i32)
+                        .ok_or_else(|| {
+                            ParseError::new(
+                                row_index + 1,
+                                format!("invalid elevation `{elevation}`"),
+                            )
+                        })
+                })
                 .collect()
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
+
+    if let Some(row_index) = grid_rows
+        .iter()
+        .position(|row| row.len() != grid_rows[0].len())
+    {
+        return Err(ParseError::new(row_index + 1, "rows differ in length"));
+    }
+
+    Ok(grid_rows)
+}
 
-    grid_rows
+/// Renders one frame per trailhead, highlighting every cell reachable from it on a hiking trail.
+pub fn export_frames(input: &str) -> Option<Frames<TrailCell>> {
+    let grid_rows = parse(input).ok()?;
+
+    let mut palette = Palette::new(Rgb(0, 0, 0));
+    for elevation in 0..=9 {
+        let t = f64::from(elevation) / 9.0;
+        palette = palette
+            .with(
+                TrailCell::Height(elevation),
+                Rgb(30, 30, 30).lerp(Rgb(200, 200, 200), t),
+            )
+            .with(
+                TrailCell::Trail(elevation),
+                Rgb(20, 90, 40).lerp(Rgb(120, 230, 120), t),
+            );
+    }
+    palette = palette.with(TrailCell::Trail(9), Rgb(250, 200, 40));
+
+    let mut frames = Frames::new(palette);
+    frames.delay = 50;
+
+    let grid = to_grid(&grid_rows);
+    let successors = graph::grid_successors(&grid, |from, to| *to == from + 1);
+
+    for start in grid.positions().filter(|&pos| grid.get(pos) == Some(&0)) {
+        let reachable: HashSet<Position> = bfs_reach(start, &successors).collect();
+        frames.push(grid.map(|pos, &elevation| {
+            if reachable.contains(&pos) {
+                TrailCell::Trail(elevation)
+            } else {
+                TrailCell::Height(elevation)
+            }
+        }));
+    }
+
+    Some(frames)
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let grid_rows = parse(input);
+    let grid_rows = parse(input).ok()?;
     let reachable_nines = count_reachable_nines(&grid_rows, true);
 
     Some(reachable_nines)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let grid_rows = parse(input);
+    let grid_rows = parse(input).ok()?;
     let reachable_nines = count_reachable_nines(&grid_rows, false);
 
     Some(reachable_nines)
 }
 
+/// Generates a `size` by `size` map of diagonal slopes, with some cells disturbed to break trails.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+
+    (0..size)
+        .map(|row| {
+            let mut line: String = (0..size)
+                .map(|col| {
+                    let height = if rng.u8(0..10) == 0 {
+                        rng.u32(0..10)
+                    } else {
+                        ((row + col) % 10) as u32
+                    };
+                    char::from_digit(height, 10).unwrap()
+                })
+                .collect();
+            line.push('\n');
+            line
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -108,4 +168,81 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(81));
     }
+
+    #[test]
+    fn test_non_square_maps() {
+        assert_eq!(part_one("0123456789"), Some(1));
+        assert_eq!(part_two("0123\n7654\n8900"), Some(1));
+    }
+
+    #[test]
+    fn test_export_frames() {
+        let frames = export_frames(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(frames.frames.len(), 9);
+        let trail_ends = frames.frames.iter().fold(0, |acc, frame| {
+            acc + frame
+                .cells
+                .iter()
+                .filter(|cell| **cell == TrailCell::Trail(9))
+                .count()
+        });
+        assert_eq!(trail_ends, 36);
+    }
+
+    fn topographic_maps() -> impl Strategy<Value = Vec<Vec<i32>>> {
+        (1..10_usize, 1..10_usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(0..10_i32, width), height)
+        })
+    }
+
+    fn to_input(grid: &[Vec<i32>]) -> String {
+        grid.iter()
+            .map(|row| row.iter().map(i32::to_string).collect::<String>() + "\n")
+            .collect()
+    }
+
+    /// Counts trails by walking every path depth-first, without any caching.
+    fn reference_trails(grid: &[Vec<i32>], (x, y): (usize, usize), ends: &mut Vec<(usize, usize)>) {
+        if grid[x][y] == 9 {
+            ends.push((x, y));
+            return;
+        }
+        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                continue;
+            };
+            if grid.get(nx).and_then(|row| row.get(ny)) == Some(&(grid[x][y] + 1)) {
+                reference_trails(grid, (nx, ny), ends);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9.\n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(grid in topographic_maps()) {
+            prop_assert_eq!(parse(&to_input(&grid)).unwrap(), grid);
+        }
+
+        #[test]
+        fn test_parts_match_reference(grid in topographic_maps()) {
+            let (mut scores, mut ratings) = (0, 0);
+            for (x, row) in grid.iter().enumerate() {
+                for (y, _) in row.iter().enumerate().filter(|(_, val)| **val == 0) {
+                    let mut ends = vec![];
+                    reference_trails(&grid, (x, y), &mut ends);
+                    ratings += ends.len();
+                    ends.sort_unstable();
+                    ends.dedup();
+                    scores += ends.len();
+                }
+            }
+            prop_assert_eq!(part_one(&to_input(&grid)), Some(scores));
+            prop_assert_eq!(part_two(&to_input(&grid)), Some(ratings));
+        }
+    }
 }
@@ -1,8 +1,16 @@
-use std::collections::HashMap;
-
-advent_of_code::solution!(11);
-
-#[derive(Eq, Hash, PartialEq, Clone, Copy)]
+use advent_of_code::counter::Counter;
+use advent_of_code::memo::memoize;
+use advent_of_code::parse::{parse_number, ParseError};
+
+advent_of_code::solution!(
+    11,
+    parse = parse,
+    reference_one = reference_part_one,
+    reference_two = reference_part_two,
+    generate = generate
+);
+
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug)]
 pub struct Stone {
     pub value: u64,
     pub n_digits: u32,
@@ -20,7 +28,7 @@ impl Stone {
     pub fn blink(&self) -> Vec<Stone> {
         if self.value == 0 {
             vec![Stone::new(1)]
-        } else if self.n_digits % 2 == 0 {
+        } else if self.n_digits.is_multiple_of(2) {
             // Avoid string conversion for better performance
             let mut left_value = self.value;
             let div = 10_u64.pow(self.n_digits / 2);
@@ -33,64 +41,76 @@ impl Stone {
     }
 }
 
-fn blink_counter(counts: HashMap<Stone, usize>, times: usize) -> HashMap<Stone, usize> {
-    let mut counts = counts;
-
-    for _ in 1..=times {
-        let mut new_counts = HashMap::new();
-        for (stone, &occurrences) in counts.iter() {
-            for new_stone in stone.blink() {
-                *new_counts.entry(new_stone).or_insert(0) += occurrences;
-            }
-        }
-        counts = new_counts;
-    }
-
-    counts
+fn blink_counter(counts: Counter<Stone>, times: usize) -> Counter<Stone> {
+    (0..times).fold(counts, |counts, _| {
+        let mut new_counts = Counter::new();
+        new_counts.extend(
+            counts
+                .iter()
+                .flat_map(|(stone, n)| stone.blink().into_iter().map(move |new| (new, n))),
+        );
+        new_counts
+    })
 }
 
-pub fn parse(input: &str) -> Vec<Stone> {
-    let stones = input
+pub fn parse(input: &str) -> Result<Vec<Stone>, ParseError> {
+    input
         .split_ascii_whitespace()
-        .filter(|location_list| !location_list.is_empty())
-        .map(|stone| {
-            let value = stone.parse::<u64>().unwrap();
-            Stone::new(value)
-        })
-        .collect();
-
-    stones
+        .map(|stone| parse_number(stone, 1).map(Stone::new))
+        .collect()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let stones = parse(input);
+    let stones = parse(input).ok()?;
+    Some(blink_counter(stones.into_iter().collect(), 25).total())
+}
 
-    let mut initial_counts = HashMap::new();
-    for stone in stones {
-        *initial_counts.entry(stone).or_insert(0) += 1;
-    }
+pub fn part_two(input: &str) -> Option<usize> {
+    let stones = parse(input).ok()?;
+    Some(blink_counter(stones.into_iter().collect(), 75).total())
+}
 
-    let final_counts = blink_counter(initial_counts, 25);
+/// Counts the stones every stone turns into with a memoised depth-first recursion instead of
+/// blinking all stones at once.
+fn count_stones_recursive(stones: &[Stone], times: usize) -> usize {
+    let mut count = memoize(|count, (stone, times): (Stone, usize)| {
+        if times == 0 {
+            1
+        } else {
+            stone
+                .blink()
+                .into_iter()
+                .map(|new| count((new, times - 1)))
+                .sum()
+        }
+    });
 
-    Some(final_counts.values().sum())
+    stones.iter().map(|stone| count((*stone, times))).sum()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let stones = parse(input);
-
-    let mut initial_counts = HashMap::new();
-    for stone in stones {
-        *initial_counts.entry(stone).or_insert(0) += 1;
-    }
+/// Counts the stones after 25 blinks with [`count_stones_recursive`], used to cross-check [`part_one`].
+pub fn reference_part_one(input: &str) -> Option<usize> {
+    Some(count_stones_recursive(&parse(input).ok()?, 25))
+}
 
-    let final_counts = blink_counter(initial_counts, 75);
+/// Counts the stones after 75 blinks with [`count_stones_recursive`], used to cross-check [`part_two`].
+pub fn reference_part_two(input: &str) -> Option<usize> {
+    Some(count_stones_recursive(&parse(input).ok()?, 75))
+}
 
-    Some(final_counts.values().sum())
+/// Generates a single line of `size` stones with values of up to six digits.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let stones: Vec<String> = (0..size)
+        .map(|_| rng.u64(0..1_000_000).to_string())
+        .collect();
+    stones.join(" ") + "\n"
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part_one() {
@@ -103,4 +123,50 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(65601038650482));
     }
+
+    fn to_input(values: &[u64]) -> String {
+        let values: Vec<String> = values.iter().map(u64::to_string).collect();
+        values.join(" ") + "\n"
+    }
+
+    /// Blinks by expanding the full row of stones every time.
+    fn reference_blink(values: &[u64], times: usize) -> usize {
+        let mut stones: Vec<Stone> = values.iter().copied().map(Stone::new).collect();
+        for _ in 0..times {
+            stones = stones.iter().flat_map(Stone::blink).collect();
+        }
+        stones.len()
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_never_panics(input in "[0-9 \n]{0,64}|\\PC*") {
+            let _ = parse(&input);
+        }
+
+        #[test]
+        fn test_parse_round_trips(values in prop::collection::vec(any::<u64>(), 0..20)) {
+            let stones = parse(&to_input(&values)).unwrap();
+            let expected: Vec<Stone> = values.iter().copied().map(Stone::new).collect();
+            prop_assert_eq!(stones, expected);
+        }
+
+        #[test]
+        fn test_blink_counter_matches_reference(
+            values in prop::collection::vec(0..100_000_u64, 1..8),
+            times in 0..12_usize,
+        ) {
+            let counts = values.iter().copied().map(Stone::new).collect();
+            prop_assert_eq!(blink_counter(counts, times).total(), reference_blink(&values, times));
+        }
+
+        #[test]
+        fn test_recursive_count_matches_reference(
+            values in prop::collection::vec(0..100_000_u64, 1..8),
+            times in 0..12_usize,
+        ) {
+            let stones: Vec<Stone> = values.iter().copied().map(Stone::new).collect();
+            prop_assert_eq!(count_stones_recursive(&stones, times), reference_blink(&values, times));
+        }
+    }
 }
@@ -0,0 +1,134 @@
+/// A multiset counting occurrences of keys, like Python's `collections.Counter`.
+use std::{
+    cmp::Reverse,
+    collections::{hash_map, HashMap},
+    hash::Hash,
+};
+
+/// Counts how often each key occurs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter<K: Eq + Hash> {
+    counts: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash> Default for Counter<K> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> Counter<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `count` occurrences of `key`.
+    pub fn add(&mut self, key: K, count: usize) {
+        *self.counts.entry(key).or_insert(0) += count;
+    }
+
+    /// Adds all occurrences counted by `other`.
+    pub fn merge(&mut self, other: Counter<K>) {
+        for (key, count) in other {
+            self.add(key, count);
+        }
+    }
+
+    /// The number of occurrences of `key`, `0` if it was never added.
+    pub fn get(&self, key: &K) -> usize {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// The number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all occurrences.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// The `n` most common keys with their counts, most common first.
+    /// Keys with equal counts are returned in an unspecified order.
+    pub fn most_common(&self, n: usize) -> Vec<(&K, usize)> {
+        let mut counts: Vec<(&K, usize)> = self.iter().collect();
+        counts.sort_by_key(|(_, count)| Reverse(*count));
+        counts.truncate(n);
+        counts
+    }
+
+    /// Iterates over the keys and their counts in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+}
+
+/// Counts every item once.
+impl<K: Eq + Hash> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter.into_iter().map(|key| (key, 1)));
+        counter
+    }
+}
+
+/// Adds the given number of occurrences of every key.
+impl<K: Eq + Hash> Extend<(K, usize)> for Counter<K> {
+    fn extend<I: IntoIterator<Item = (K, usize)>>(&mut self, iter: I) {
+        for (key, count) in iter {
+            self.add(key, count);
+        }
+    }
+}
+
+impl<K: Eq + Hash> IntoIterator for Counter<K> {
+    type Item = (K, usize);
+    type IntoIter = hash_map::IntoIter<K, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn counts_items() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(counter.most_common(1), vec![(&'a', 5)]);
+    }
+
+    #[test]
+    fn returns_most_common_first() {
+        let counter: Counter<char> = "aaabbc".chars().collect();
+        assert_eq!(counter.most_common(2), vec![(&'a', 3), (&'b', 2)]);
+        assert_eq!(counter.most_common(10).len(), 3);
+    }
+
+    #[test]
+    fn merges_counters() {
+        let mut counter: Counter<u8> = [1, 1, 2].into_iter().collect();
+        counter.merge([2, 3].into_iter().collect());
+        counter.add(3, 4);
+
+        let mut counts: Vec<(u8, usize)> = counter.into_iter().collect();
+        counts.sort_unstable();
+        assert_eq!(counts, vec![(1, 2), (2, 2), (3, 5)]);
+    }
+}
@@ -0,0 +1,506 @@
+/// Exports grid snapshots as SVG, PNG or animated GIF images.
+/// Files are written to `data/exports/<day>/`.
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs::{self, File},
+    hash::Hash,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+};
+
+use crate::grid::Grid;
+use crate::template::Day;
+
+/// A colour in RGB space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    /// Linearly interpolates between two colours, `t` being clamped to `0.0..=1.0`.
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+/// Maps cell values to colours. Values without an entry use the default colour.
+#[derive(Debug, Clone)]
+pub struct Palette<T> {
+    colours: HashMap<T, Rgb>,
+    default: Rgb,
+}
+
+impl<T: Hash + Eq> Palette<T> {
+    pub fn new(default: Rgb) -> Self {
+        Self {
+            colours: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Assigns `colour` to cells equal to `value`.
+    #[must_use]
+    pub fn with(mut self, value: T, colour: Rgb) -> Self {
+        self.colours.insert(value, colour);
+        self
+    }
+
+    pub fn colour(&self, value: &T) -> Rgb {
+        self.colours.get(value).copied().unwrap_or(self.default)
+    }
+
+    /// Every distinct colour the palette can produce, the default colour first.
+    fn distinct_colours(&self) -> Vec<Rgb> {
+        let mut colours = vec![self.default];
+        for colour in self.colours.values() {
+            if !colours.contains(colour) {
+                colours.push(*colour);
+            }
+        }
+        colours
+    }
+}
+
+/// A sequence of grid snapshots rendered with a shared palette.
+#[derive(Debug, Clone)]
+pub struct Frames<T> {
+    pub palette: Palette<T>,
+    pub frames: Vec<Grid<T>>,
+    /// Edge length of a single cell in pixels.
+    pub cell_size: usize,
+    /// Delay between two GIF frames in hundredths of a second.
+    pub delay: u16,
+}
+
+impl<T: Hash + Eq> Frames<T> {
+    pub fn new(palette: Palette<T>) -> Self {
+        Self {
+            palette,
+            frames: vec![],
+            cell_size: 4,
+            delay: 5,
+        }
+    }
+
+    pub fn push(&mut self, frame: Grid<T>) {
+        self.frames.push(frame);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Svg,
+    Png,
+    Gif,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Png => "png",
+            ExportFormat::Gif => "gif",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "svg" => Ok(ExportFormat::Svg),
+            "png" => Ok(ExportFormat::Png),
+            "gif" => Ok(ExportFormat::Gif),
+            _ => Err(Error::UnknownFormat(s.into())),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    UnknownFormat(String),
+    EmptyFrames,
+    Encoding(String),
+    IO(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    fn from(e: png::EncodingError) -> Self {
+        Error::Encoding(e.to_string())
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(e: gif::EncodingError) -> Self {
+        Error::Encoding(e.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownFormat(format) => {
+                write!(
+                    f,
+                    "unknown export format `{format}`, expected svg, png or gif."
+                )
+            }
+            Error::EmptyFrames => write!(f, "there are no frames to export."),
+            Error::Encoding(e) => write!(f, "failed to encode image: {e}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/* -------------------------------------------------------------------------- */
+
+#[must_use]
+pub fn get_export_dir(day: Day) -> PathBuf {
+    Path::new("data").join("exports").join(day.to_string())
+}
+
+/// Renders a single grid as an SVG document.
+pub fn to_svg<T: Hash + Eq>(grid: &Grid<T>, palette: &Palette<T>, cell_size: usize) -> String {
+    let width = grid.width * cell_size;
+    let height = grid.height * cell_size;
+
+    let mut lines = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        ),
+        format!(
+            r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+            palette.default.to_hex()
+        ),
+    ];
+
+    // NOTE: merge horizontal runs of the same colour to keep the document small.
+    for (row_index, row) in grid.rows().enumerate() {
+        let mut col = 0;
+        while col < row.len() {
+            let colour = palette.colour(&row[col]);
+            let run = row[col..]
+                .iter()
+                .take_while(|cell| palette.colour(cell) == colour)
+                .count();
+            if colour != palette.default {
+                lines.push(format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{cell_size}" fill="{}"/>"#,
+                    col * cell_size,
+                    row_index * cell_size,
+                    run * cell_size,
+                    colour.to_hex()
+                ));
+            }
+            col += run;
+        }
+    }
+
+    lines.push("</svg>".into());
+    lines.join("\n")
+}
+
+/// Renders a single grid to RGB pixel data, row by row.
+fn to_pixels<T: Hash + Eq>(
+    grid: &Grid<T>,
+    cell_size: usize,
+    mut pixel: impl FnMut(&T) -> [u8; 3],
+    channels: usize,
+) -> Vec<u8> {
+    let width = grid.width * cell_size;
+    let mut data = Vec::with_capacity(width * grid.height * cell_size * channels);
+
+    for row in grid.rows() {
+        let mut line = Vec::with_capacity(width * channels);
+        for cell in row {
+            let value = pixel(cell);
+            for _ in 0..cell_size {
+                line.extend_from_slice(&value[..channels]);
+            }
+        }
+        for _ in 0..cell_size {
+            data.extend_from_slice(&line);
+        }
+    }
+
+    data
+}
+
+pub fn write_svg<T: Hash + Eq>(
+    path: &Path,
+    grid: &Grid<T>,
+    palette: &Palette<T>,
+    cell_size: usize,
+) -> Result<(), Error> {
+    fs::write(path, to_svg(grid, palette, cell_size))?;
+    Ok(())
+}
+
+pub fn write_png<T: Hash + Eq>(
+    path: &Path,
+    grid: &Grid<T>,
+    palette: &Palette<T>,
+    cell_size: usize,
+) -> Result<(), Error> {
+    let file = BufWriter::new(File::create(path)?);
+    let (width, height) = dimensions(grid, cell_size)?;
+
+    let mut encoder = png::Encoder::new(file, u32::from(width), u32::from(height));
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data = to_pixels(
+        grid,
+        cell_size,
+        |cell| {
+            let Rgb(r, g, b) = palette.colour(cell);
+            [r, g, b]
+        },
+        3,
+    );
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Writes all frames into a single looping GIF. Frames must share the dimensions of the first one.
+pub fn write_gif<T: Hash + Eq>(path: &Path, frames: &Frames<T>) -> Result<(), Error> {
+    let first = frames.frames.first().ok_or(Error::EmptyFrames)?;
+    let (width, height) = dimensions(first, frames.cell_size)?;
+
+    let colours = frames.palette.distinct_colours();
+    if colours.len() > 256 {
+        return Err(Error::Encoding(
+            "GIF export supports at most 256 palette colours.".into(),
+        ));
+    }
+    let global_palette: Vec<u8> = colours.iter().flat_map(|c| [c.0, c.1, c.2]).collect();
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &global_palette)?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    for grid in &frames.frames {
+        if (grid.width, grid.height) != (first.width, first.height) {
+            return Err(Error::Encoding(
+                "all GIF frames must have the same dimensions.".into(),
+            ));
+        }
+
+        let pixels = to_pixels(
+            grid,
+            frames.cell_size,
+            |cell| {
+                let colour = frames.palette.colour(cell);
+                #[allow(clippy::cast_possible_truncation)]
+                let index = colours.iter().position(|c| *c == colour).unwrap_or(0) as u8;
+                [index, 0, 0]
+            },
+            1,
+        );
+
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+        frame.delay = frames.delay;
+        encoder.write_frame(&frame)?;
+    }
+
+    Ok(())
+}
+
+fn dimensions<T>(grid: &Grid<T>, cell_size: usize) -> Result<(u16, u16), Error> {
+    let to_u16 = |value: usize| {
+        u16::try_from(value).map_err(|_| Error::Encoding("image dimensions are too large.".into()))
+    };
+    Ok((
+        to_u16(grid.width * cell_size)?,
+        to_u16(grid.height * cell_size)?,
+    ))
+}
+
+/// Writes `frames` to the export directory of `day`.
+/// SVG and PNG produce one numbered file per frame, replacing the frames of earlier exports in
+/// the same format. GIF produces a single animation.
+pub fn export<T: Hash + Eq>(
+    frames: &Frames<T>,
+    day: Day,
+    format: ExportFormat,
+) -> Result<Vec<PathBuf>, Error> {
+    if frames.frames.is_empty() {
+        return Err(Error::EmptyFrames);
+    }
+
+    let dir = get_export_dir(day);
+    fs::create_dir_all(&dir)?;
+
+    if format == ExportFormat::Gif {
+        let path = dir.join("animation.gif");
+        write_gif(&path, frames)?;
+        return Ok(vec![path]);
+    }
+
+    // frames left over from an export with more frames would end up in the animation.
+    remove_frames(&dir, format)?;
+
+    frames
+        .frames
+        .iter()
+        .enumerate()
+        .map(|(index, grid)| {
+            let path = dir.join(format!("frame-{index:04}.{format}"));
+            match format {
+                ExportFormat::Svg => write_svg(&path, grid, &frames.palette, frames.cell_size),
+                _ => write_png(&path, grid, &frames.palette, frames.cell_size),
+            }?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Removes the numbered frame files of `format` from `dir`.
+fn remove_frames(dir: &Path, format: ExportFormat) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_frame = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with("frame-") && name.ends_with(&format!(".{format}"))
+            });
+        if is_frame {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse the `--export <format>` argument passed to a solution and, if present, export the frames
+/// built by `build_frames`.
+pub fn export_from_args<T: Hash + Eq>(
+    build_frames: impl Fn(&str) -> Option<Frames<T>>,
+    input: &str,
+    day: Day,
+) {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(format_index) = args.iter().position(|x| x == "--export").map(|i| i + 1) else {
+        return;
+    };
+
+    let format = match args.get(format_index).map(|s| s.parse::<ExportFormat>()) {
+        Some(Ok(format)) => format,
+        Some(Err(e)) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        None => {
+            eprintln!("Unexpected command-line input. Format: cargo solve 6 --export png");
+            process::exit(1);
+        }
+    };
+
+    let Some(frames) = build_frames(input) else {
+        eprintln!("Could not build frames from the input.");
+        process::exit(1);
+    };
+
+    match export(&frames, day, format) {
+        Ok(paths) => {
+            println!("---");
+            println!(
+                "🎄 Exported {} {format} file(s) to \"{}\".",
+                paths.len(),
+                get_export_dir(day).display()
+            );
+        }
+        Err(e) => {
+            eprintln!("Failed to export frames: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{remove_frames, to_svg, ExportFormat, Palette, Rgb};
+    use crate::grid::Grid;
+    use std::fs;
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("PNG".parse::<ExportFormat>().unwrap(), ExportFormat::Png);
+        assert!("bmp".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn renders_svg_runs() {
+        let grid = Grid::parse("##.\n...", |_, c| c);
+        let palette = Palette::new(Rgb(255, 255, 255)).with('#', Rgb(0, 0, 0));
+        let svg = to_svg(&grid, &palette, 2);
+        assert!(svg.contains(r##"<rect x="0" y="0" width="4" height="2" fill="#000000"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 2);
+    }
+
+    #[test]
+    fn interpolates_colours() {
+        assert_eq!(Rgb(0, 0, 0).lerp(Rgb(200, 100, 50), 0.5), Rgb(100, 50, 25));
+    }
+
+    #[test]
+    fn removes_stale_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "frame-0000.svg",
+            "frame-0001.svg",
+            "frame-0000.png",
+            "animation.gif",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        remove_frames(&dir, ExportFormat::Svg).unwrap();
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort_unstable();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(left, vec!["animation.gif", "frame-0000.png"]);
+    }
+}
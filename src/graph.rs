@@ -0,0 +1,431 @@
+/// Graph algorithms over the shared [`Grid`] and explicit edge lists, built on `pathfinding`.
+use pathfinding::num_traits::Zero;
+use pathfinding::prelude::{astar, bfs, connected_components, dijkstra, topological_sort};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::export::Frames;
+use crate::grid::{Grid, Position};
+
+/// A path found by one of the search algorithms, from its start to its goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Path<N, C> {
+    fn from_pathfinding((nodes, cost): (Vec<N>, C)) -> Self {
+        Path { nodes, cost }
+    }
+}
+
+impl<C> Path<Position, C> {
+    /// Copies `grid` with every cell on the path replaced by `mark`, e.g. to export it.
+    pub fn overlay<T: Clone>(&self, grid: &Grid<T>, mark: T) -> Grid<T> {
+        let mut grid = grid.clone();
+        for position in &self.nodes {
+            grid.set(*position, mark.clone());
+        }
+        grid
+    }
+
+    /// Pushes one frame per step onto `frames`, drawing the path as it is walked.
+    pub fn push_frames<T: Clone + Hash + Eq>(
+        &self,
+        frames: &mut Frames<T>,
+        grid: &Grid<T>,
+        mark: T,
+    ) {
+        let mut grid = grid.clone();
+        for position in &self.nodes {
+            grid.set(*position, mark.clone());
+            frames.push(grid.clone());
+        }
+    }
+}
+
+/// Finds the cheapest path from `start` to a node matching `success` with Dijkstra's algorithm.
+pub fn shortest_path<N, C, I>(
+    start: &N,
+    successors: impl FnMut(&N) -> I,
+    success: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra(start, successors, success).map(Path::from_pathfinding)
+}
+
+/// Finds the cheapest path from `start` to a node matching `success` with A*. `heuristic` must
+/// never overestimate the remaining cost, see [`manhattan`] for grids.
+pub fn shortest_path_astar<N, C, I>(
+    start: &N,
+    successors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    success: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, heuristic, success).map(Path::from_pathfinding)
+}
+
+/// The manhattan distance between two positions, an admissible A* heuristic on grids without
+/// diagonal moves.
+pub fn manhattan(a: Position, b: Position) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Builds the successors of a position on `grid`: the orthogonal neighbours for which
+/// `can_move(from, to)` holds for the cells moved between.
+pub fn grid_successors<'a, T>(
+    grid: &'a Grid<T>,
+    can_move: impl Fn(&T, &T) -> bool + 'a,
+) -> impl Fn(&Position) -> Vec<Position> + 'a {
+    move |&position| {
+        let Some(from) = grid.get(position) else {
+            return vec![];
+        };
+        grid.neighbours(position)
+            .filter(|&to| grid.get(to).is_some_and(|to| can_move(from, to)))
+            .collect()
+    }
+}
+
+/// Like [`grid_successors`], for weighted moves. `cost(from, to)` is [`None`] if the move is not
+/// possible.
+pub fn grid_weighted_successors<'a, T, C>(
+    grid: &'a Grid<T>,
+    cost: impl Fn(&T, &T) -> Option<C> + 'a,
+) -> impl Fn(&Position) -> Vec<(Position, C)> + 'a {
+    move |&position| {
+        let Some(from) = grid.get(position) else {
+            return vec![];
+        };
+        grid.neighbours(position)
+            .filter_map(|to| Some((to, cost(from, grid.get(to)?)?)))
+            .collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A directed graph stored as adjacency lists, with edge costs of type `C`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph<N: Eq + Hash, C = usize> {
+    edges: HashMap<N, Vec<(N, C)>>,
+}
+
+impl<N: Eq + Hash, C> Default for Graph<N, C> {
+    fn default() -> Self {
+        Graph {
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Graph<N, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.edges.entry(node).or_default();
+    }
+
+    /// Adds an edge from `from` to `to`, adding both nodes if they are not part of the graph yet.
+    pub fn add_edge(&mut self, from: N, to: N, cost: C) {
+        self.add_node(to.clone());
+        self.edges.entry(from).or_default().push((to, cost));
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.edges.contains_key(node)
+    }
+
+    /// Iterates over all nodes in an unspecified order.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    /// The outgoing edges of `node`, empty if it is not part of the graph.
+    pub fn successors(&self, node: &N) -> &[(N, C)] {
+        self.edges.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// The graph induced by the nodes matching `keep`: those nodes and the edges between them.
+    pub fn subgraph(&self, mut keep: impl FnMut(&N) -> bool) -> Self {
+        let nodes: HashSet<&N> = self.nodes().filter(|node| keep(node)).collect();
+
+        Graph {
+            edges: nodes
+                .iter()
+                .map(|&node| {
+                    let edges = self
+                        .successors(node)
+                        .iter()
+                        .filter(|(to, _)| nodes.contains(to))
+                        .cloned()
+                        .collect();
+                    (node.clone(), edges)
+                })
+                .collect(),
+        }
+    }
+
+    /// Orders the nodes so that every edge points forward. If the graph has a cycle, returns it
+    /// instead, as a list of nodes that starts and ends with the same node.
+    /// Nodes that no edge orders relative to each other come out in an unspecified order, see
+    /// [`Graph::topological_sort_from`] for a deterministic one.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Vec<N>> {
+        let roots: Vec<N> = self.nodes().cloned().collect();
+        let successors = |node: &N| self.successors(node).iter().map(|(to, _)| to.clone());
+
+        topological_sort(&roots, successors).map_err(|node| self.cycle_through(&node))
+    }
+
+    /// Like [`Graph::topological_sort`], but every node comes out as early in `order` as the edges
+    /// allow, so nodes left unordered by the edges keep their order in it. Nodes missing from
+    /// `order` follow in an unspecified order.
+    pub fn topological_sort_from(&self, order: &[N]) -> Result<Vec<N>, Vec<N>> {
+        let mut nodes: Vec<&N> = Vec::with_capacity(self.edges.len());
+        let mut index: HashMap<&N, usize> = HashMap::with_capacity(self.edges.len());
+        for node in order
+            .iter()
+            .filter(|node| self.contains(node))
+            .chain(self.nodes())
+        {
+            index.entry(node).or_insert_with(|| {
+                nodes.push(node);
+                nodes.len() - 1
+            });
+        }
+
+        let mut in_degree = vec![0; nodes.len()];
+        for node in &nodes {
+            for (to, _) in self.successors(node) {
+                in_degree[index[to]] += 1;
+            }
+        }
+
+        // Kahn's algorithm, always taking the earliest node in `order` among those ready.
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+            .filter(|&node| in_degree[node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut sorted = Vec::with_capacity(nodes.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            sorted.push(nodes[node].clone());
+            for (to, _) in self.successors(nodes[node]) {
+                let to = index[to];
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+
+        if sorted.len() == nodes.len() {
+            return Ok(sorted);
+        }
+
+        // the nodes left over lie on a cycle or behind one.
+        let roots: Vec<N> = (0..nodes.len())
+            .filter(|&node| in_degree[node] > 0)
+            .map(|node| nodes[node].clone())
+            .collect();
+        let successors = |node: &N| self.successors(node).iter().map(|(to, _)| to.clone());
+        match topological_sort(&roots, successors) {
+            Err(node) => Err(self.cycle_through(&node)),
+            Ok(_) => unreachable!("nodes left over by Kahn's algorithm contain a cycle"),
+        }
+    }
+
+    /// The shortest cycle through `node`, as found by a breadth-first search from each successor.
+    fn cycle_through(&self, node: &N) -> Vec<N> {
+        let successors = |node: &N| {
+            self.successors(node)
+                .iter()
+                .map(|(to, _)| to.clone())
+                .collect::<Vec<_>>()
+        };
+
+        successors(node)
+            .iter()
+            .filter_map(|start| bfs(start, successors, |n| n == node))
+            .min_by_key(Vec::len)
+            .map(|path| [vec![node.clone()], path].concat())
+            .unwrap_or_else(|| vec![node.clone()])
+    }
+
+    /// Groups the nodes into connected components, ignoring the direction of edges.
+    pub fn connected_components(&self) -> Vec<HashSet<N>> {
+        let mut neighbours: HashMap<&N, Vec<N>> = HashMap::new();
+        for (from, edges) in &self.edges {
+            neighbours.entry(from).or_default();
+            for (to, _) in edges {
+                neighbours.entry(from).or_default().push(to.clone());
+                neighbours.entry(to).or_default().push(from.clone());
+            }
+        }
+
+        let starts: Vec<N> = self.nodes().cloned().collect();
+        connected_components(&starts, |node| neighbours[node].clone())
+    }
+}
+
+impl<N: Eq + Hash + Clone, C: Zero + Ord + Copy> Graph<N, C> {
+    /// The cheapest path from `start` to `goal`, see [`shortest_path`].
+    pub fn shortest_path(&self, start: &N, goal: &N) -> Option<Path<N, C>> {
+        shortest_path(
+            start,
+            |node| self.successors(node).to_vec(),
+            |node| node == goal,
+        )
+    }
+
+    /// The cheapest path from `start` to `goal` guided by `heuristic`, see [`shortest_path_astar`].
+    pub fn shortest_path_astar(
+        &self,
+        start: &N,
+        goal: &N,
+        heuristic: impl FnMut(&N) -> C,
+    ) -> Option<Path<N, C>> {
+        shortest_path_astar(
+            start,
+            |node| self.successors(node).to_vec(),
+            heuristic,
+            |node| node == goal,
+        )
+    }
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    /// Builds a graph from `(from, to)` edges, each with a cost of `1`.
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to, 1);
+        }
+        graph
+    }
+
+    /// Builds a graph from adjacency lists, such as ordering rules mapping a node to every node
+    /// that has to come after it.
+    pub fn from_adjacency(adjacency: &HashMap<N, Vec<N>>) -> Self {
+        Self::from_edges(
+            adjacency
+                .iter()
+                .flat_map(|(from, to)| to.iter().map(move |to| (from.clone(), to.clone()))),
+        )
+    }
+}
+
+impl Graph<Position> {
+    /// Builds a graph over every position of `grid`, with an edge for each move to an orthogonal
+    /// neighbour allowed by `can_move(from, to)`.
+    pub fn from_grid<T>(grid: &Grid<T>, can_move: impl Fn(&T, &T) -> bool) -> Self {
+        let successors = grid_successors(grid, can_move);
+        let mut graph = Graph::new();
+        for position in grid.positions() {
+            graph.add_node(position);
+            for to in successors(&position) {
+                graph.add_edge(position, to, 1);
+            }
+        }
+        graph
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{grid_weighted_successors, manhattan, shortest_path_astar, Graph};
+    use crate::grid::Grid;
+    use std::collections::HashMap;
+
+    #[test]
+    fn sorts_topologically() {
+        let rules = HashMap::from([(47, vec![53, 13]), (97, vec![47, 13]), (53, vec![13])]);
+        let order = Graph::from_adjacency(&rules).topological_sort().unwrap();
+        assert_eq!(order, vec![97, 47, 53, 13]);
+    }
+
+    #[test]
+    fn sorts_topologically_from_an_order() {
+        let graph = Graph::from_edges([(3, 1), (5, 4)]);
+        for _ in 0..10 {
+            let order = graph.topological_sort_from(&[1, 2, 3, 4, 5]).unwrap();
+            assert_eq!(order, vec![3, 1, 5, 4]);
+        }
+
+        let mut graph = Graph::from_edges([(1, 2), (2, 3), (3, 1)]);
+        graph.add_edge(0, 1, 1);
+        let cycle = graph.topological_sort_from(&[0, 1, 2, 3]).unwrap_err();
+        assert_eq!(cycle.len(), 4);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = Graph::from_edges([(1, 2), (2, 3), (3, 1), (3, 4)]);
+        let cycle = graph.topological_sort().unwrap_err();
+
+        assert_eq!(cycle.len(), 4);
+        assert_eq!(cycle.first(), cycle.last());
+        assert!(cycle.windows(2).all(|edge| graph
+            .successors(&edge[0])
+            .iter()
+            .any(|(to, _)| *to == edge[1])));
+    }
+
+    #[test]
+    fn restricts_to_subgraphs() {
+        let graph = Graph::from_edges([(1, 2), (2, 3), (3, 1)]);
+        assert!(graph.subgraph(|node| *node != 3).topological_sort().is_ok());
+    }
+
+    #[test]
+    fn finds_connected_components() {
+        let graph = Graph::from_edges([(1, 2), (3, 2), (4, 5)]);
+        let mut sizes: Vec<usize> = graph
+            .connected_components()
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![2, 3]);
+    }
+
+    #[test]
+    fn finds_shortest_paths_on_grids() {
+        let grid = Grid::parse("..#.\n.##.\n....", |_, c| c);
+        let graph = Graph::from_grid(&grid, |_, to| *to != '#');
+
+        let path = graph.shortest_path(&(0, 0), &(0, 3)).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes.len(), 8);
+        assert_eq!(path.overlay(&grid, 'O').to_string(), "O.#O\nO##O\nOOOO");
+        assert_eq!(graph.shortest_path(&(0, 0), &(0, 2)), None);
+    }
+
+    #[test]
+    fn finds_weighted_paths_with_astar() {
+        let grid = Grid::parse("131\n191\n111", |_, c| c.to_digit(10).unwrap() as usize);
+        let successors = grid_weighted_successors(&grid, |_, to| Some(*to));
+        let goal = (2, 2);
+
+        let path =
+            shortest_path_astar(&(0, 0), successors, |&p| manhattan(p, goal), |&p| p == goal)
+                .unwrap();
+        assert_eq!(path.cost, 4);
+    }
+}
@@ -0,0 +1,205 @@
+/// A rectangular grid shared by the puzzle solutions.
+use std::fmt::Display;
+
+/// A `(row, column)` position inside a [`Grid`].
+pub type Position = (usize, usize);
+
+/// The four orthogonal offsets, in the order up, right, down, left.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// All eight offsets, clockwise starting with up.
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells. Panics if the cell count does not match the dimensions.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "expected {width}x{height} cells, got {}",
+            cells.len()
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Creates a grid with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Builds a grid from a block of text, mapping every character through `cell`.
+    /// Empty lines are skipped and the width is taken from the longest line,
+    /// missing cells at the end of shorter lines are mapped from `' '`.
+    pub fn parse(input: &str, mut cell: impl FnMut(Position, char) -> T) -> Self {
+        let lines: Vec<&str> = input
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            let mut chars = line.chars();
+            for col in 0..width {
+                cells.push(cell((row, col), chars.next().unwrap_or(' ')));
+            }
+        }
+
+        Self::new(width, lines.len(), cells)
+    }
+
+    pub fn contains(&self, (row, col): Position) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Replaces the cell at `position`, returning the previous value if it was inside the grid.
+    pub fn set(&mut self, position: Position, value: T) -> Option<T> {
+        self.get_mut(position)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Moves `position` by `offset`, returning [`None`] if the result leaves the grid.
+    pub fn offset(&self, (row, col): Position, (d_row, d_col): (isize, isize)) -> Option<Position> {
+        let position = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+        self.contains(position).then_some(position)
+    }
+
+    /// Positions orthogonally adjacent to `position` that are inside the grid.
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |offset| self.offset(position, *offset))
+    }
+
+    /// Positions adjacent to `position`, including diagonals, that are inside the grid.
+    pub fn neighbours_diagonal(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ALL_DIRECTIONS
+            .iter()
+            .filter_map(move |offset| self.offset(position, *offset))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Iterates over every cell together with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Returns the position of the first cell matching `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// Creates a new grid of the same size by mapping every cell.
+    pub fn map<U>(&self, mut f: impl FnMut(Position, &T) -> U) -> Grid<U> {
+        Grid::new(
+            self.width,
+            self.height,
+            self.iter()
+                .map(|(position, cell)| f(position, cell))
+                .collect(),
+        )
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        self.contains(position)
+            .then_some(position.0 * self.width + position.1)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn parses_rectangular_grids() {
+        let grid = Grid::parse("ab.\n.cd\n", |_, c| c);
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid.get((1, 2)), Some(&'d'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "ab.\n.cd");
+    }
+
+    #[test]
+    fn offsets_stay_inside_grid() {
+        let grid = Grid::filled(3, 2, 0);
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (1, 2)), Some((1, 2)));
+        assert_eq!(grid.neighbours((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours_diagonal((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn maps_and_finds_cells() {
+        let mut grid = Grid::parse("123\n456", |_, c| c.to_digit(10).unwrap());
+        assert_eq!(grid.set((0, 0), 9), Some(1));
+        assert_eq!(grid.find(|cell| *cell == 5), Some((1, 1)));
+        let doubled = grid.map(|_, cell| cell * 2);
+        assert_eq!(doubled.cells, vec![18, 4, 6, 8, 10, 12]);
+    }
+}
@@ -1,3 +1,10 @@
+pub mod counter;
+pub mod export;
+pub mod graph;
+pub mod grid;
+pub mod memo;
+pub mod parse;
 pub mod template;
+pub mod word_search;
 
 // Use this file to add helper functions and additional modules.
@@ -1,4 +1,6 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, crosscheck, download, inputs, read, readme, report, scaffold, solve, status, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,6 +9,8 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
+    use advent_of_code::export::ExportFormat;
+    use advent_of_code::template::commands::inputs::Action;
     use advent_of_code::template::Day;
     use std::process;
 
@@ -14,27 +18,51 @@ mod args {
         Download {
             day: Day,
         },
+        Inputs {
+            action: Action,
+            day: Option<Day>,
+        },
         Read {
             day: Day,
         },
+        Readme,
+        Report {
+            output: Option<String>,
+        },
         Scaffold {
             day: Day,
             download: bool,
             overwrite: bool,
+            template: Option<String>,
+            example_one: Option<String>,
+            example_two: Option<String>,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
+            parallel: bool,
             submit: Option<u8>,
+            export: Option<ExportFormat>,
         },
         All {
             release: bool,
+            parallel: bool,
+            changed: bool,
+        },
+        Status {
+            json: bool,
+        },
+        Crosscheck {
+            day: Day,
+            release: bool,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            scale: bool,
+            parallel: bool,
         },
         #[cfg(feature = "today")]
         Today,
@@ -46,33 +74,59 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                parallel: args.contains("--parallel"),
+                changed: args.contains("--changed"),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let scale = args.contains("--scale");
+                let parallel = args.contains("--parallel");
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    scale,
+                    parallel,
                 }
             }
+            Some("status") => AppArguments::Status {
+                json: args.contains("--json"),
+            },
+            Some("crosscheck") => AppArguments::Crosscheck {
+                day: args.free_from_str()?,
+                release: args.contains("--release"),
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
+            Some("inputs") => AppArguments::Inputs {
+                action: args.free_from_str()?,
+                day: args.opt_free_from_str()?,
+            },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
+            Some("readme") => AppArguments::Readme,
+            Some("report") => AppArguments::Report {
+                output: args.opt_value_from_str("--output")?,
+            },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
+                template: args.opt_value_from_str("--template")?,
+                example_one: args.opt_value_from_str("--example-one")?,
+                example_two: args.opt_value_from_str("--example-two")?,
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                parallel: args.contains("--parallel"),
+                export: args.opt_value_from_str("--export")?,
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -102,32 +156,64 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::All {
+                release,
+                parallel,
+                changed,
+            } => all::handle(release, parallel, changed),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                scale,
+                parallel,
+            } => {
+                if scale {
+                    time::handle_scale(day, parallel);
+                } else {
+                    time::handle(day, all, store, parallel);
+                }
+            }
+            AppArguments::Status { json } => status::handle(json),
+            AppArguments::Crosscheck { day, release } => crosscheck::handle(day, release),
             AppArguments::Download { day } => download::handle(day),
+            AppArguments::Inputs { action, day } => inputs::handle(&action, day),
             AppArguments::Read { day } => read::handle(day),
+            AppArguments::Readme => readme::handle(),
+            AppArguments::Report { output } => report::handle(output),
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
+                template,
+                example_one,
+                example_two,
             } => {
-                scaffold::handle(day, overwrite);
+                // download first, so that the template can use the puzzle title.
                 if download {
                     download::handle(day);
                 }
+                scaffold::handle(
+                    day,
+                    overwrite,
+                    template.as_deref(),
+                    [example_one, example_two],
+                );
             }
             AppArguments::Solve {
                 day,
                 release,
                 dhat,
+                parallel,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                export,
+            } => solve::handle(day, release, dhat, parallel, submit, export),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
                         download::handle(day);
+                        scaffold::handle(day, false, None, [None, None]);
                         read::handle(day)
                     }
                     None => {
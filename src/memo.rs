@@ -0,0 +1,122 @@
+/// Memoisation for recursive functions.
+use std::{collections::HashMap, hash::Hash};
+
+/// A cache of computed values, for memoised recursion that needs extra state. The recursive
+/// function takes the cache and calls itself from [`Memo::get_or_insert_with`].
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value cached for `key`, computing it with `f` if there is none.
+    /// `f` gets the cache itself, so that it can recurse.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+
+        let value = f(self);
+        self.values.insert(key, value.clone());
+        value
+    }
+
+    /// The number of cached values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Memoises a recursive function. `f` gets a function to recurse with in place of itself.
+pub fn memoize<K: Eq + Hash + Clone, V: Clone>(
+    f: fn(&mut dyn FnMut(K) -> V, K) -> V,
+) -> impl FnMut(K) -> V {
+    fn call<K: Eq + Hash + Clone, V: Clone>(
+        memo: &mut Memo<K, V>,
+        f: fn(&mut dyn FnMut(K) -> V, K) -> V,
+        key: K,
+    ) -> V {
+        memo.get_or_insert_with(key.clone(), |memo| f(&mut |key| call(memo, f, key), key))
+    }
+
+    let mut memo = Memo::new();
+    move |key| call(&mut memo, f, key)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{memoize, Memo};
+
+    #[test]
+    fn caches_values() {
+        let mut memo: Memo<u32, u32> = Memo::new();
+        let mut calls = 0;
+
+        for _ in 0..3 {
+            let value = memo.get_or_insert_with(7, |_| {
+                calls += 1;
+                49
+            });
+            assert_eq!(value, 49);
+        }
+
+        assert_eq!(calls, 1);
+        assert_eq!(memo.len(), 1);
+    }
+
+    #[test]
+    fn recurses_through_the_cache() {
+        fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+            memo.get_or_insert_with(n, |memo| match n {
+                0 | 1 => n,
+                _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+            })
+        }
+
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+    }
+
+    #[test]
+    fn memoizes_fibonacci() {
+        let mut fibonacci = memoize(|fibonacci, n: u64| match n {
+            0 | 1 => n,
+            _ => fibonacci(n - 1) + fibonacci(n - 2),
+        });
+
+        assert_eq!(fibonacci(90), 2_880_067_194_370_816_120);
+    }
+
+    #[test]
+    fn memoizes_recursion() {
+        // the number of lattice paths through a grid, exponential without memoisation.
+        let mut paths = memoize(|paths, (x, y): (u64, u64)| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths((x - 1, y)) + paths((x, y - 1))
+            }
+        });
+
+        assert_eq!(paths((16, 16)), 601_080_390);
+    }
+}
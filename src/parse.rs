@@ -0,0 +1,60 @@
+/// Shared error handling for the puzzle input parsers.
+use std::{error::Error, fmt::Display, str::FromStr};
+
+/// An error which can be returned when parsing puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based line number the error occurred on, `0` if it is not tied to a line.
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+/// Parses `s` into a number, reporting the offending value and line on failure.
+pub fn parse_number<T: FromStr>(s: &str, line: usize) -> Result<T, ParseError> {
+    s.trim()
+        .parse()
+        .map_err(|_| ParseError::new(line, format!("expected a number, found `{s}`")))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_number, ParseError};
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse_number::<u32>(" 42", 1), Ok(42));
+        assert_eq!(
+            parse_number::<u32>("x", 3),
+            Err(ParseError::new(3, "expected a number, found `x`"))
+        );
+    }
+
+    #[test]
+    fn formats_errors() {
+        assert_eq!(ParseError::new(2, "oops").to_string(), "line 2: oops");
+        assert_eq!(ParseError::new(0, "oops").to_string(), "oops");
+    }
+}
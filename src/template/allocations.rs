@@ -0,0 +1,53 @@
+/// A global allocator that counts allocations while enabled, used to report allocation counts
+/// next to benchmark timings. It is replaced by `dhat` when the `dhat-heap` feature is active.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn record(&self) {
+        if ENABLED.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.record();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.record();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.record();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+/// Runs `f`, returning its result and the number of allocations (including reallocations) it
+/// made. The count is [`None`] when the counting allocator is not installed.
+pub fn count<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+    if cfg!(feature = "dhat-heap") {
+        return (f(), None);
+    }
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+    let result = f();
+    ENABLED.store(false, Ordering::Relaxed);
+
+    (result, Some(ALLOCATIONS.load(Ordering::Relaxed) - before))
+}
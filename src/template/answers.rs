@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static ANSWERS_FILE_PATH: &str = "./data/answers.json";
+
+/// A solved puzzle part, with the accepted answer if it was recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solved {
+    pub answer: Option<String>,
+}
+
+/// Represents the solved parts of a single day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub day: Day,
+    pub part_1: Option<Solved>,
+    pub part_2: Option<Solved>,
+}
+
+impl Answer {
+    /// The solved `part` of the day, [`None`] if it is unsolved or not a part of a puzzle.
+    pub fn part(&self, part: u8) -> Option<&Solved> {
+        match part {
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn stars(&self) -> usize {
+        usize::from(self.part_1.is_some()) + usize::from(self.part_2.is_some())
+    }
+}
+
+/// Represents the solved parts for a set of days.
+/// Can be serialized from / to JSON, where a part is either `null`, `true` when it was solved
+/// without recording the answer, or the accepted answer as a string.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(ANSWERS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns empty answers.
+    pub fn read_from_file() -> Self {
+        let Ok(s) = fs::read_to_string(ANSWERS_FILE_PATH) else {
+            return Answers::default();
+        };
+
+        match Answers::try_from(s) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("{e}");
+                Answers::default()
+            }
+        }
+    }
+
+    pub fn get(&self, day: Day) -> Option<&Answer> {
+        self.data.iter().find(|answer| answer.day == day)
+    }
+
+    /// Marks `part` of `day` as solved. A known answer replaces a previously recorded one,
+    /// an unknown answer never overwrites a known one. Parts other than 1 and 2 are not recorded.
+    pub fn record(&self, day: Day, part: u8, answer: Option<String>) -> Self {
+        debug_assert!(
+            matches!(part, 1 | 2),
+            "puzzles only have parts 1 and 2, got {part}"
+        );
+        if !matches!(part, 1 | 2) {
+            return self.clone();
+        }
+
+        let mut data = self.data.clone();
+
+        let index = data.iter().position(|a| a.day == day).unwrap_or_else(|| {
+            data.push(Answer {
+                day,
+                part_1: None,
+                part_2: None,
+            });
+            data.len() - 1
+        });
+
+        let entry = &mut data[index];
+        let slot = match part {
+            1 => &mut entry.part_1,
+            2 => &mut entry.part_2,
+            _ => unreachable!(),
+        };
+
+        if answer.is_some() || slot.is_none() {
+            *slot = Some(Solved { answer });
+        }
+
+        data.sort_unstable_by_key(|a| a.day);
+        Answers { data }
+    }
+
+    pub fn total_stars(&self) -> usize {
+        self.data.iter().map(Answer::stars).sum()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn solved_to_json(value: Option<&Solved>) -> JsonValue {
+    match value {
+        None => JsonValue::Null,
+        Some(Solved { answer: None }) => JsonValue::Boolean(true),
+        Some(Solved {
+            answer: Some(answer),
+        }) => JsonValue::String(answer.clone()),
+    }
+}
+
+fn solved_from_json(value: Option<&JsonValue>) -> Option<Option<Solved>> {
+    match value {
+        None | Some(JsonValue::Null | JsonValue::Boolean(false)) => Some(None),
+        Some(JsonValue::Boolean(true)) => Some(Some(Solved { answer: None })),
+        Some(JsonValue::String(answer)) => Some(Some(Solved {
+            answer: Some(answer.clone()),
+        })),
+        Some(_) => None,
+    }
+}
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1".into(), solved_to_json(value.part_1.as_ref()));
+        map.insert("part_2".into(), solved_to_json(value.part_2.as_ref()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        let part_1 = solved_from_json(json.get("part_1"))
+            .ok_or("Expected answer.part_1 to be null, a boolean or a string.")?;
+
+        let part_2 = solved_from_json(json.get("part_2"))
+            .ok_or("Expected answer.part_2 to be null, a boolean or a string.")?;
+
+        Ok(Answer {
+            day,
+            part_1,
+            part_2,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answers, Solved};
+    use crate::day;
+
+    #[test]
+    fn handles_json_answers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": "42", "part_2": true }, { "day": "02", "part_1": true, "part_2": null }] }"#.to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(
+            answers.get(day!(1)).unwrap().part(1),
+            Some(&Solved {
+                answer: Some("42".into())
+            })
+        );
+        assert_eq!(answers.get(day!(2)).unwrap().part_2, None);
+        assert_eq!(answers.get(day!(1)).unwrap().part(3), None);
+        assert_eq!(answers.total_stars(), 3);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let answers = Answers::default()
+            .record(day!(3), 2, Some("7".into()))
+            .record(day!(1), 1, None);
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Answers::try_from(json).unwrap(), answers);
+    }
+
+    #[test]
+    fn keeps_known_answers() {
+        let answers = Answers::default()
+            .record(day!(1), 1, Some("42".into()))
+            .record(day!(1), 1, None);
+        assert_eq!(
+            answers.get(day!(1)).unwrap().part_1,
+            Some(Solved {
+                answer: Some("42".into())
+            })
+        );
+        assert_eq!(answers.data.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_unknown_parts() {
+        Answers::default().record(day!(1), 3, None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": 3 }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+}
@@ -25,6 +25,14 @@ impl Display for AocCommandError {
     }
 }
 
+/// The verdict on a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    Correct,
+    AlreadySolved,
+    Rejected,
+}
+
 pub fn check() -> Result<(), AocCommandError> {
     Command::new("aoc")
         .arg("-V")
@@ -72,23 +80,45 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
     Ok(output)
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+/// Submits an answer. The output is captured to find out whether it was accepted, then echoed.
+pub fn submit(day: Day, part: u8, result: &str) -> Result<Submission, AocCommandError> {
     // workaround: the argument order is inverted for submit.
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    let output = Command::new("aoc")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    print!("{stdout}");
+
+    if !output.status.success() {
+        return Err(AocCommandError::BadExitStatus(output));
+    }
+
+    Ok(if stdout.contains("That's the right answer") {
+        Submission::Correct
+    } else if stdout.contains("Did you already complete it") {
+        Submission::AlreadySolved
+    } else {
+        Submission::Rejected
+    })
 }
 
 fn get_input_path(day: Day) -> String {
     format!("data/inputs/{day}.txt")
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match std::env::var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
@@ -0,0 +1,267 @@
+/// A cache of solution outputs keyed by a hash of everything that can change them, used by
+/// `cargo all --changed` to skip days whose source, shared code and input are unchanged.
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{inputs, Day};
+
+static CACHE_FILE_PATH: &str = "./data/cache.json";
+
+/// Shared code lives in `src/`, except for the solutions in `src/bin`.
+static SHARED_DIR: &str = "./src";
+static SOLUTIONS_DIR: &str = "./src/bin";
+
+/// The output of a successful run of a day's solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub day: Day,
+    pub hash: String,
+    pub output: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cache {
+    pub data: Vec<Entry>,
+}
+
+impl Cache {
+    /// Dehydrate the cache to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(CACHE_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate the cache from a JSON file. If not present or invalid, returns an empty cache.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(CACHE_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(Cache::try_from)
+            .unwrap_or_default()
+    }
+
+    /// The cached output of `day`, if it was produced by sources with the same `hash`.
+    pub fn get(&self, day: Day, hash: &str) -> Option<&[String]> {
+        self.data
+            .iter()
+            .find(|entry| entry.day == day && entry.hash == hash)
+            .map(|entry| entry.output.as_slice())
+    }
+
+    pub fn insert(&mut self, day: Day, hash: String, output: Vec<String>) {
+        self.remove(day);
+        self.data.push(Entry { day, hash, output });
+        self.data.sort_unstable_by_key(|entry| entry.day);
+    }
+
+    pub fn remove(&mut self, day: Day) {
+        self.data.retain(|entry| entry.day != day);
+    }
+}
+
+/// Hashes the shared library code and the manifest, which every solution depends on.
+pub fn shared_hash() -> String {
+    let mut files = vec![PathBuf::from("Cargo.toml")];
+    collect_files(Path::new(SHARED_DIR), &mut files);
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for path in files {
+        hash_file(&mut hasher, &path);
+    }
+    hex(&hasher.finalize())
+}
+
+/// Hashes the source and input of `day` together with the `shared` hash, the build profile and
+/// whether the `parallel` feature is enabled.
+pub fn day_hash(day: Day, shared: &str, is_release: bool, is_parallel: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(shared.as_bytes());
+    hasher.update(if is_release { b"release" } else { b"debug  " });
+    hasher.update(if is_parallel {
+        b"parallel"
+    } else {
+        b"serial  "
+    });
+
+    hash_file(&mut hasher, Path::new(&get_path_for_bin(day)));
+    hash_file(&mut hasher, &inputs::get_plaintext_path(day));
+    hash_file(&mut hasher, &inputs::get_encrypted_path(day));
+
+    hex(&hasher.finalize())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.is_dir() {
+            if path != Path::new(SOLUTIONS_DIR) {
+                collect_files(&path, files);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Adds the path and contents of a file to `hasher`. Missing files are hashed as such, so that
+/// creating or deleting e.g. an input changes the hash.
+fn hash_file(hasher: &mut Sha256, path: &Path) {
+    hasher.update(path.to_string_lossy().as_bytes());
+    match fs::read(path) {
+        Ok(contents) => {
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(contents);
+        }
+        Err(_) => hasher.update(b"missing"),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Cache> for JsonValue {
+    fn from(value: Cache) -> Self {
+        let entries = value
+            .data
+            .iter()
+            .map(|entry| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("day".into(), JsonValue::String(entry.day.to_string()));
+                map.insert("hash".into(), JsonValue::String(entry.hash.clone()));
+                map.insert(
+                    "output".into(),
+                    JsonValue::Array(
+                        entry
+                            .output
+                            .iter()
+                            .cloned()
+                            .map(JsonValue::String)
+                            .collect(),
+                    ),
+                );
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("data".into(), JsonValue::Array(entries));
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Cache {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        let data = json_data
+            .iter()
+            .map(|entry| {
+                let entry = entry
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("Expected cache entry to be a JSON object.")?;
+
+                let day = entry
+                    .get("day")
+                    .and_then(|v| v.get::<String>())
+                    .and_then(|day| Day::from_str(day).ok())
+                    .ok_or("Expected entry.day to be a Day struct.")?;
+
+                let hash = entry
+                    .get("hash")
+                    .and_then(|v| v.get::<String>())
+                    .ok_or("Expected entry.hash to be a string.")?;
+
+                let output = entry
+                    .get("output")
+                    .and_then(|v| v.get::<Vec<JsonValue>>())
+                    .ok_or("Expected entry.output to be an array.")?
+                    .iter()
+                    .map(|line| line.get::<String>().cloned())
+                    .collect::<Option<_>>()
+                    .ok_or("Expected entry.output to contain strings.")?;
+
+                Ok(Entry {
+                    day,
+                    hash: hash.clone(),
+                    output,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Cache { data })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{day_hash, Cache};
+    use crate::day;
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut cache = Cache::default();
+        cache.insert(
+            day!(2),
+            "b".into(),
+            vec!["Part 1: \u{1b}[1m2\u{1b}[0m".into()],
+        );
+        cache.insert(day!(1), "a".into(), vec![]);
+
+        let json = tinyjson::JsonValue::from(cache.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Cache::try_from(json).unwrap(), cache);
+    }
+
+    #[test]
+    fn looks_up_entries_by_hash() {
+        let mut cache = Cache::default();
+        cache.insert(day!(1), "a".into(), vec!["Part 1: 1".into()]);
+        cache.insert(day!(1), "b".into(), vec!["Part 1: 2".into()]);
+
+        assert_eq!(cache.get(day!(1), "a"), None);
+        assert_eq!(
+            cache.get(day!(1), "b"),
+            Some(&["Part 1: 2".to_string()][..])
+        );
+        assert_eq!(cache.data.len(), 1);
+    }
+
+    #[test]
+    fn hashes_depend_on_profile_and_shared_code() {
+        let hash =
+            |shared, is_release, is_parallel| day_hash(day!(1), shared, is_release, is_parallel);
+        assert_ne!(hash("x", true, false), hash("x", false, false));
+        assert_ne!(hash("x", true, false), hash("x", true, true));
+        assert_ne!(hash("x", true, false), hash("y", true, false));
+        assert_eq!(hash("x", true, false), hash("x", true, false));
+    }
+}
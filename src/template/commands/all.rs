@@ -1,5 +1,84 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::answers::Answers;
+use crate::template::cache::Cache;
+use crate::template::run_multi::{child_commands::parse_answer, run_multi};
+use crate::template::{all_days, ANSI_BOLD, ANSI_RESET};
+
+/// Runs every solution. With `changed_only`, days whose sources and input did not change reuse
+/// their cached output, and all results are verified against the recorded answers.
+pub fn handle(is_release: bool, is_parallel: bool, changed_only: bool) {
+    if !changed_only {
+        run_multi(
+            &all_days().collect(),
+            is_release,
+            is_parallel,
+            false,
+            false,
+            None,
+        );
+        return;
+    }
+
+    let mut cache = Cache::read_from_file();
+    run_multi(
+        &all_days().collect(),
+        is_release,
+        is_parallel,
+        false,
+        false,
+        Some(&mut cache),
+    );
+
+    if let Err(e) = cache.store_file() {
+        eprintln!("Failed to store cache: {e}");
+    }
+
+    if !verify(&cache) {
+        process::exit(1);
+    }
+}
+
+/// Compares the results of every day with the answers recorded in `data/answers.json`.
+fn verify(cache: &Cache) -> bool {
+    let answers = Answers::read_from_file();
+    let mut verified = 0;
+    let mut mismatches = vec![];
+
+    for answer in &answers.data {
+        for part in 1..=2 {
+            let Some(expected) = answer.part(part).and_then(|solved| solved.answer.as_ref()) else {
+                continue;
+            };
+
+            let actual = cache
+                .data
+                .iter()
+                .find(|entry| entry.day == answer.day)
+                .and_then(|entry| parse_answer(&entry.output, part));
+
+            if actual.as_ref() == Some(expected) {
+                verified += 1;
+            } else {
+                mismatches.push(format!(
+                    "Day {} part {part}: expected {expected}, got {}",
+                    answer.day,
+                    actual.as_deref().unwrap_or("no result")
+                ));
+            }
+        }
+    }
+
+    println!();
+    if verified + mismatches.len() == 0 {
+        println!("No recorded answers to verify.");
+        return true;
+    }
+
+    println!("{ANSI_BOLD}Verified answers:{ANSI_RESET} {verified} ✔");
+    for mismatch in &mismatches {
+        eprintln!("✖ {mismatch}");
+    }
+
+    mismatches.is_empty()
 }
@@ -0,0 +1,26 @@
+use std::process::{self, Command, Stdio};
+
+use crate::template::Day;
+
+pub fn handle(day: Day, release: bool) {
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+
+    if release {
+        cmd_args.push("--release".to_string());
+    }
+
+    cmd_args.push("--".to_string());
+    cmd_args.push("--crosscheck".to_string());
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
@@ -0,0 +1,111 @@
+use std::process;
+use std::str::FromStr;
+
+use crate::template::inputs::{self, Error, Outcome};
+use crate::template::{all_days, Day};
+
+pub enum Action {
+    Encrypt,
+    Decrypt,
+    Status,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "encrypt" => Ok(Action::Encrypt),
+            "decrypt" => Ok(Action::Decrypt),
+            "status" => Ok(Action::Status),
+            _ => Err(format!(
+                "unknown action `{s}`, expected one of: encrypt, decrypt, status"
+            )),
+        }
+    }
+}
+
+pub fn handle(action: &Action, day: Option<Day>) {
+    match action {
+        Action::Encrypt => transform(day, "Encrypted", inputs::encrypt_day),
+        Action::Decrypt => transform(day, "Decrypted", inputs::decrypt_day),
+        Action::Status => status(day),
+    }
+}
+
+/// Applies `apply` to `day`, or to every day that has something to process if none is given.
+fn transform(day: Option<Day>, verb: &str, apply: impl Fn(&str, Day) -> Result<Outcome, Error>) {
+    let passphrase = match inputs::load_passphrase() {
+        Ok(passphrase) => passphrase,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let single = day.is_some();
+    let mut failed = false;
+
+    for day in days(day) {
+        match apply(&passphrase, day) {
+            Ok(Outcome::Written) => println!("{verb} input for day {day}."),
+            Ok(Outcome::Unchanged) => println!("Input for day {day} is up to date."),
+            // when processing every day, days without an input are expected.
+            Err(Error::MissingInput) if !single => {}
+            Err(e) => {
+                eprintln!("Failed to process input for day {day}: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn status(day: Option<Day>) {
+    let passphrase = inputs::load_passphrase().ok();
+    if passphrase.is_none() {
+        println!(
+            "No key found, set {} or create \"{}\" to check whether inputs are in sync.\n",
+            inputs::KEY_ENV,
+            inputs::KEY_FILE
+        );
+    }
+
+    let single = day.is_some();
+
+    println!("| Day | Input | Encrypted | In sync |");
+    println!("| :---: | :---: | :---: | :---: |");
+
+    for day in days(day) {
+        let status = inputs::status(passphrase.as_deref(), day);
+        if !status.plaintext && !status.encrypted && !single {
+            continue;
+        }
+
+        let in_sync = match status.in_sync {
+            Some(true) => "✔",
+            Some(false) => "✖",
+            None => "?",
+        };
+        println!(
+            "| {day} | {} | {} | {in_sync} |",
+            mark(status.plaintext),
+            mark(status.encrypted)
+        );
+    }
+}
+
+fn days(day: Option<Day>) -> Vec<Day> {
+    day.map_or_else(|| all_days().collect(), |day| vec![day])
+}
+
+fn mark(present: bool) -> &'static str {
+    if present {
+        "✔"
+    } else {
+        "✖"
+    }
+}
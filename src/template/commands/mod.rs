@@ -1,6 +1,11 @@
 pub mod all;
+pub mod crosscheck;
 pub mod download;
+pub mod inputs;
 pub mod read;
+pub mod readme;
+pub mod report;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
 pub mod time;
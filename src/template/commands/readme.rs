@@ -0,0 +1,35 @@
+use std::process;
+
+use crate::template::answers::Answers;
+use crate::template::timings::Timings;
+use crate::template::{readme_benchmarks, readme_stars};
+
+/// Regenerates the stars and benchmark tables in the readme from the stored data.
+pub fn handle() {
+    let mut failed = false;
+
+    match readme_stars::update(&Answers::read_from_file()) {
+        Ok(()) => println!("Updated stars."),
+        Err(_) => {
+            eprintln!("Failed to update stars.");
+            failed = true;
+        }
+    }
+
+    let timings = Timings::read_from_file();
+    if timings.data.is_empty() {
+        println!("No stored benchmarks, run `cargo time --store` first.");
+    } else {
+        match readme_benchmarks::update(timings) {
+            Ok(()) => println!("Updated benchmarks."),
+            Err(_) => {
+                eprintln!("Failed to update benchmarks.");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,32 @@
+use std::{fs, path::Path, process};
+
+use crate::template::history::History;
+use crate::template::report;
+use crate::template::samples;
+use crate::template::timings::Timings;
+
+static DEFAULT_REPORT_PATH: &str = "./data/exports/report.html";
+
+/// Writes an offline HTML report of the stored benchmarks to `output`, or to `data/exports`.
+pub fn handle(output: Option<String>) {
+    let timings = Timings::read_from_file();
+    if timings.data.is_empty() {
+        eprintln!("No stored benchmarks, run `cargo time --store` first.");
+        process::exit(1);
+    }
+
+    let html = report::render(&timings, &History::read_from_file(), &samples::read_all());
+
+    let path = output.unwrap_or_else(|| DEFAULT_REPORT_PATH.into());
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).ok();
+    }
+
+    match fs::write(&path, html) {
+        Ok(()) => println!("Wrote report to \"{path}\"."),
+        Err(e) => {
+            eprintln!("Failed to write report: {e}");
+            process::exit(1);
+        }
+    }
+}
@@ -1,14 +1,13 @@
 use std::{
     fs::{File, OpenOptions},
     io::Write,
+    path::Path,
     process,
 };
 
+use crate::template::presets::{self, Placeholders};
 use crate::template::Day;
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
-
 fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
     let mut file = OpenOptions::new();
     if overwrite {
@@ -27,7 +26,43 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
         .open(path)
 }
 
-pub fn handle(day: Day, overwrite: bool) {
+/// Creates an empty data file, keeping one that already has contents (e.g. a downloaded input).
+fn create_data_file(path: &str, kind: &str) {
+    if Path::new(path).metadata().is_ok_and(|m| m.len() > 0) {
+        println!("Kept existing {kind} file \"{path}\"");
+        return;
+    }
+
+    match create_file(path) {
+        Ok(_) => {
+            println!("Created empty {kind} file \"{path}\"");
+        }
+        Err(e) => {
+            eprintln!("Failed to create {kind} file: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Scaffolds `day` from the template preset `template`, see `templates/`.
+/// `examples` are the expected answers to the example of each part, if known.
+pub fn handle(day: Day, overwrite: bool, template: Option<&str>, examples: [Option<String>; 2]) {
+    let template_name = template.unwrap_or(if day.is_single_part() {
+        presets::SINGLE_PART_PRESET
+    } else {
+        presets::DEFAULT_PRESET
+    });
+    let template = match presets::load(template_name) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("Failed to load template: {e}");
+            process::exit(1);
+        }
+    };
+
+    let [example_one, example_two] = examples;
+    let placeholders = Placeholders::for_day(day, example_one, example_two);
+
     let input_path = format!("data/inputs/{day}.txt");
     let example_path = format!("data/examples/{day}.txt");
     let module_path = format!("src/bin/{day}.rs");
@@ -40,11 +75,7 @@ pub fn handle(day: Day, overwrite: bool) {
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
+    match file.write_all(presets::render(&template, day, &placeholders).as_bytes()) {
         Ok(()) => {
             println!("Created module file \"{}\"", &module_path);
         }
@@ -54,25 +85,8 @@ pub fn handle(day: Day, overwrite: bool) {
         }
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
-        }
-    }
-
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
-        }
-    }
+    create_data_file(&input_path, "input");
+    create_data_file(&example_path, "example");
 
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
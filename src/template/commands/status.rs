@@ -0,0 +1,105 @@
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::history::History;
+use crate::template::status::{DayStatus, Sources};
+use crate::template::timings::Timings;
+use crate::template::{all_days, aoc_cli, Day};
+
+/// Prints the status of every day as a table, or as JSON with `json`.
+pub fn handle(json: bool) {
+    #[allow(clippy::cast_possible_wrap)]
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs() as i64);
+
+    let sources = Sources {
+        answers: Answers::read_from_file(),
+        timings: Timings::read_from_file(),
+        history: History::read_from_file(),
+        year: aoc_cli::get_year(),
+        now,
+    };
+
+    let statuses: Vec<DayStatus> = all_days()
+        .map(|day| {
+            let mut status = DayStatus::collect(day, &sources, None);
+            if status.scaffolded {
+                status.tests = Some(run_tests(day));
+            }
+            status
+        })
+        .collect();
+
+    if json {
+        let json = JsonValue::Array(statuses.iter().map(JsonValue::from).collect());
+        println!("{}", json.format().unwrap_or_default());
+        return;
+    }
+
+    println!(
+        "| Day | Scaffolded | Input | Example | Tests | Solved | Answers | Benchmark | Unlocked |"
+    );
+    println!("| :---: | :---: | :---: | :---: | :---: | :---: | :---: | :---: | :---: |");
+
+    for status in &statuses {
+        let benchmark = status.benchmark.as_ref().map_or_else(
+            || "-".into(),
+            |benchmark| {
+                let total = format!("{:.2}ms", benchmark.total_nanos / 1_000_000_f64);
+                match benchmark.timestamp {
+                    #[allow(clippy::cast_possible_wrap)]
+                    Some(timestamp) => {
+                        format!("{total}, {}", ago((now - timestamp as i64) / 86_400))
+                    }
+                    None => total,
+                }
+            },
+        );
+
+        let unlocked = match status.days_since_unlock {
+            Some(days) if days < 0 => format!("in {}d", -days),
+            Some(days) => ago(days),
+            None => "?".into(),
+        };
+
+        println!(
+            "| {} | {} | {} | {} | {} | {}/2 | {}/2 | {benchmark} | {unlocked} |",
+            status.day,
+            mark(status.scaffolded),
+            mark(status.input),
+            mark(status.example),
+            status.tests.map_or("-", mark),
+            status.solved,
+            status.answers,
+        );
+    }
+}
+
+/// Runs the tests of a scaffolded day, discarding their output.
+fn run_tests(day: Day) -> bool {
+    Command::new("cargo")
+        .args(["test", "--quiet", "--bin", &day.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn ago(days: i64) -> String {
+    if days == 0 {
+        "today".into()
+    } else {
+        format!("{days}d ago")
+    }
+}
+
+fn mark(value: bool) -> &'static str {
+    if value {
+        "✔"
+    } else {
+        "✖"
+    }
+}
@@ -1,10 +1,13 @@
 use std::collections::HashSet;
+use std::process::{self, Command, Stdio};
 
+use crate::template::answers::Answers;
+use crate::template::history::History;
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, readme_stars, Day};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(day: Option<Day>, run_all: bool, store: bool, is_parallel: bool) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,11 +24,15 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, is_parallel, true, store, None).unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
+        History::read_from_file()
+            .push(&timings)
+            .store_file()
+            .unwrap();
 
         println!();
         match readme_benchmarks::update(merged_timings) {
@@ -36,5 +43,43 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
                 eprintln!("Failed to store updated benchmarks.");
             }
         }
+
+        if readme_stars::update(&Answers::read_from_file()).is_err() {
+            eprintln!("Failed to update stars.");
+        }
+    }
+}
+
+/// Times a single day on generated inputs of increasing size.
+pub fn handle_scale(day: Option<Day>, is_parallel: bool) {
+    let Some(day) = day else {
+        eprintln!("`--scale` needs a day. Format: cargo time 9 --scale");
+        process::exit(1);
+    };
+
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--release".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+    ];
+
+    if is_parallel {
+        cmd_args.extend(["--features".to_string(), "parallel".to_string()]);
+    }
+
+    cmd_args.extend(["--".to_string(), "--scale".to_string()]);
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
     }
 }
@@ -0,0 +1,307 @@
+/// Compares solution parts against registered reference implementations.
+/// Both are run on the real input, every example and randomly generated inputs.
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::{env, fs, process};
+
+use crate::template::{inputs, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Number of random inputs checked per part.
+const RANDOM_INPUTS: u64 = 200;
+
+type PartFn = Box<dyn Fn(&str) -> Option<String>>;
+
+/// Generates a puzzle input from a size parameter and a seed.
+pub type Generator = fn(usize, u64) -> String;
+
+struct CheckedPart {
+    part: u8,
+    solution: PartFn,
+    reference: PartFn,
+}
+
+/// The outcome of running a part function: its result, or the panic message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Value(Option<String>),
+    Panicked(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Value(Some(value)) => write!(f, "{value}"),
+            Outcome::Value(None) => write!(f, "✖"),
+            Outcome::Panicked(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+pub struct Crosscheck {
+    day: Day,
+    parts: Vec<CheckedPart>,
+    generator: Option<Generator>,
+}
+
+impl Crosscheck {
+    pub fn new(day: Day) -> Self {
+        Self {
+            day,
+            parts: vec![],
+            generator: None,
+        }
+    }
+
+    /// Registers `reference` as an alternative implementation of `solution`.
+    pub fn add_part<T: Display, R: Display>(
+        &mut self,
+        part: u8,
+        solution: impl Fn(&str) -> Option<T> + 'static,
+        reference: impl Fn(&str) -> Option<R> + 'static,
+    ) {
+        self.parts.push(CheckedPart {
+            part,
+            solution: Box::new(move |input| solution(input).map(|x| x.to_string())),
+            reference: Box::new(move |input| reference(input).map(|x| x.to_string())),
+        });
+    }
+
+    /// Registers a generator used to produce random inputs.
+    pub fn set_generator(&mut self, generator: Generator) {
+        self.generator = Some(generator);
+    }
+
+    /// Whether the solution was invoked with `--crosscheck`.
+    pub fn is_requested() -> bool {
+        env::args().any(|x| x == "--crosscheck")
+    }
+
+    /// Run all checks, exiting with a non-zero status if any part diverges.
+    pub fn run(&self) {
+        if self.parts.is_empty() {
+            eprintln!(
+                "Day {} has no reference implementations. Register them with `solution!({}, reference_one = ..., reference_two = ...)`.",
+                self.day,
+                self.day.into_inner()
+            );
+            process::exit(1);
+        }
+
+        // NOTE: panics are reported as divergences, silence the default hook while checking.
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let inputs = self.fixed_inputs();
+        let mut diverged = false;
+
+        for checked in &self.parts {
+            for (name, input) in &inputs {
+                diverged |= !self.check_input(checked, name, input);
+            }
+            diverged |= !self.check_random(checked);
+        }
+
+        panic::set_hook(default_hook);
+
+        if diverged {
+            process::exit(1);
+        }
+    }
+
+    /// The real input and every example file for the day, if present. Like solutions, inputs
+    /// missing in plaintext are decrypted from their encrypted copy.
+    fn fixed_inputs(&self) -> Vec<(String, String)> {
+        let mut inputs = vec![];
+
+        let input_path = inputs::get_plaintext_path(self.day);
+        let encrypted_path = inputs::get_encrypted_path(self.day);
+        match fs::read_to_string(&input_path) {
+            Ok(input) if !input.trim().is_empty() => {
+                inputs.push((input_path.display().to_string(), input));
+            }
+            _ if encrypted_path.exists() => match inputs::read_encrypted(self.day) {
+                Ok(input) => inputs.push((encrypted_path.display().to_string(), input)),
+                Err(e) => eprintln!("Skipping {}: {e}", encrypted_path.display()),
+            },
+            _ => {}
+        }
+
+        let mut examples: Vec<_> = fs::read_dir("data/examples")
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name == format!("{}.txt", self.day)
+                            || name.starts_with(&format!("{}-", self.day))
+                    })
+            })
+            .collect();
+        examples.sort();
+
+        for path in examples {
+            if let Ok(input) = fs::read_to_string(&path) {
+                inputs.push((path.display().to_string(), input));
+            }
+        }
+
+        inputs
+    }
+
+    fn check_input(&self, checked: &CheckedPart, name: &str, input: &str) -> bool {
+        let (solution, reference) = run_both(checked, input);
+
+        if solution == reference {
+            println!(
+                "Part {}: {name} ✔ {ANSI_ITALIC}({solution}){ANSI_RESET}",
+                checked.part
+            );
+            return true;
+        }
+
+        println!(
+            "Part {}: {ANSI_BOLD}{name} diverges{ANSI_RESET}",
+            checked.part
+        );
+        print_divergence(input, &solution, &reference);
+        false
+    }
+
+    fn check_random(&self, checked: &CheckedPart) -> bool {
+        let Some(generator) = self.generator else {
+            println!(
+                "Part {}: {ANSI_ITALIC}no generator registered, skipping random inputs{ANSI_RESET}",
+                checked.part
+            );
+            return true;
+        };
+
+        for seed in 0..RANDOM_INPUTS {
+            // NOTE: grow the inputs slowly, small inputs make for readable counterexamples.
+            #[allow(clippy::cast_possible_truncation)]
+            let size = 1 + (seed / 10) as usize;
+            let input = generator(size, seed);
+            let (solution, reference) = run_both(checked, &input);
+
+            if solution != reference {
+                println!(
+                    "Part {}: {ANSI_BOLD}random input diverges{ANSI_RESET} (size {size}, seed {seed})",
+                    checked.part
+                );
+                let minimised = minimise(&input, |candidate| {
+                    let (solution, reference) = run_both(checked, candidate);
+                    solution != reference
+                });
+                let (solution, reference) = run_both(checked, &minimised);
+                print_divergence(&minimised, &solution, &reference);
+                return false;
+            }
+        }
+
+        println!("Part {}: {RANDOM_INPUTS} random inputs ✔", checked.part);
+        true
+    }
+}
+
+fn run_once(func: &PartFn, input: &str) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| func(input))) {
+        Ok(value) => Outcome::Value(value),
+        Err(payload) => Outcome::Panicked(
+            payload
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default(),
+        ),
+    }
+}
+
+fn run_both(checked: &CheckedPart, input: &str) -> (Outcome, Outcome) {
+    (
+        run_once(&checked.solution, input),
+        run_once(&checked.reference, input),
+    )
+}
+
+fn print_divergence(input: &str, solution: &Outcome, reference: &Outcome) {
+    println!("  solution:  {solution}");
+    println!("  reference: {reference}");
+    println!("  input ({} bytes):", input.len());
+    for line in input.lines() {
+        println!("  | {line}");
+    }
+}
+
+/// Shrinks `input` while `diverges` holds, removing chunks of lines for multi-line inputs
+/// and chunks of characters for single-line inputs (delta debugging).
+pub fn minimise(input: &str, diverges: impl Fn(&str) -> bool) -> String {
+    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+
+    if lines.len() > 1 {
+        let units: Vec<String> = lines.iter().map(|line| format!("{line}\n")).collect();
+        let minimal = minimise_units(units, &diverges);
+        minimal.concat()
+    } else {
+        let trimmed = input.trim_end();
+        let units: Vec<String> = trimmed.chars().map(String::from).collect();
+        let minimal = minimise_units(units, &|candidate: &str| {
+            diverges(&format!("{candidate}\n"))
+        });
+        format!("{}\n", minimal.concat())
+    }
+}
+
+fn minimise_units(mut units: Vec<String>, diverges: &impl Fn(&str) -> bool) -> Vec<String> {
+    let mut chunk_size = units.len() / 2;
+
+    while chunk_size > 0 {
+        let mut start = 0;
+        let mut removed_any = false;
+
+        while start < units.len() {
+            let end = (start + chunk_size).min(units.len());
+            let candidate: Vec<String> = units[..start]
+                .iter()
+                .chain(units[end..].iter())
+                .cloned()
+                .collect();
+
+            if !candidate.is_empty() && diverges(&candidate.concat()) {
+                units = candidate;
+                removed_any = true;
+            } else {
+                start += chunk_size;
+            }
+        }
+
+        if !removed_any {
+            chunk_size /= 2;
+        }
+    }
+
+    units
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::minimise;
+
+    #[test]
+    fn minimises_lines() {
+        let input = "1\n2\n3\n4\n5\n6\n";
+        let minimal = minimise(input, |candidate| candidate.contains('4'));
+        assert_eq!(minimal, "4\n");
+    }
+
+    #[test]
+    fn minimises_characters() {
+        let input = "2333133121414131402\n";
+        let minimal = minimise(input, |candidate| candidate.matches('4').count() >= 2);
+        assert_eq!(minimal, "44\n");
+    }
+}
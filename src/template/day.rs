@@ -41,6 +41,12 @@ impl Day {
     pub fn into_inner(self) -> u8 {
         self.0
     }
+
+    /// Whether the puzzle of this day has only one part. The second star of the last day is
+    /// awarded for collecting every other star, so there is no second part to solve.
+    pub fn is_single_part(self) -> bool {
+        self.0 == 25
+    }
 }
 
 #[cfg(feature = "today")]
@@ -187,6 +193,12 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn single_part_days() {
+        assert!(Day(25).is_single_part());
+        assert!(!Day(24).is_single_part());
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::timings::{Timing, Timings};
+
+static HISTORY_FILE_PATH: &str = "./data/timings-history.json";
+
+/// A set of timings stored by one `cargo time --store` invocation.
+#[derive(Clone, Debug)]
+pub struct Run {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub timings: Timings,
+}
+
+/// Every stored run, oldest first. Unlike `timings.json`, which only keeps the latest timing of
+/// every day, this keeps each run so that reports can show how timings evolved.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub runs: Vec<Run>,
+}
+
+impl History {
+    /// Dehydrate the history to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(HISTORY_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate the history from a JSON file. If not present, returns an empty history.
+    pub fn read_from_file() -> Self {
+        let Ok(s) = fs::read_to_string(HISTORY_FILE_PATH) else {
+            return History::default();
+        };
+
+        match History::try_from(s) {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("{e}");
+                History::default()
+            }
+        }
+    }
+
+    /// Appends `timings` as a new run, stamped with the current time.
+    pub fn push(&self, timings: &Timings) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+
+        let mut runs = self.runs.clone();
+        runs.push(Run {
+            timestamp,
+            timings: timings.clone(),
+        });
+        History { runs }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<History> for JsonValue {
+    fn from(value: History) -> Self {
+        let runs = value
+            .runs
+            .iter()
+            .map(|run| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                #[allow(clippy::cast_precision_loss)]
+                map.insert("timestamp".into(), JsonValue::Number(run.timestamp as f64));
+                map.insert(
+                    "data".into(),
+                    JsonValue::Array(run.timings.data.iter().map(JsonValue::from).collect()),
+                );
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("runs".into(), JsonValue::Array(runs));
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_runs = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("runs")
+            .ok_or("expected JSON document to have key `runs`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.runs` to be an array.")?;
+
+        let runs = json_runs
+            .iter()
+            .map(|run| {
+                let run = run
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("Expected run to be a JSON object.")?;
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let timestamp = run
+                    .get("timestamp")
+                    .and_then(|v| v.get::<f64>())
+                    .map(|x| *x as u64)
+                    .ok_or("Expected run.timestamp to be a number.")?;
+
+                let data = run
+                    .get("data")
+                    .and_then(|v| v.get::<Vec<JsonValue>>())
+                    .ok_or("Expected run.data to be an array.")?
+                    .iter()
+                    .map(Timing::try_from)
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Run {
+                    timestamp,
+                    timings: Timings { data },
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(History { runs })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::History;
+    use crate::day;
+    use crate::template::timings::{Timing, Timings};
+
+    #[test]
+    fn round_trips_through_json() {
+        let timings = Timings {
+            data: vec![Timing {
+                part_1: Some("1ms".into()),
+                total_nanos: 1e6,
+                ..Timing::new(day!(1))
+            }],
+        };
+        let history = History::default().push(&timings).push(&timings);
+        let json = tinyjson::JsonValue::from(history).stringify().unwrap();
+        let history = History::try_from(json).unwrap();
+
+        assert_eq!(history.runs.len(), 2);
+        assert_eq!(history.runs[1].timings.data[0].day, day!(1));
+        assert_eq!(
+            history.runs[1].timings.data[0].part_1,
+            Some("1ms".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_history() {
+        History::try_from(r#"{ "runs": [{ "data": [] }] }"#.to_string()).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+/// Encrypted storage of puzzle inputs, so they can be committed without publishing them.
+/// Every input is stored next to its plaintext as `data/inputs/NN.txt.enc`, encrypted with
+/// ChaCha20-Poly1305 under a key derived from a local passphrase with Argon2id and a random salt
+/// stored in the file, so that guessing the passphrase offline is slow.
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::template::Day;
+
+/// Environment variable holding the passphrase.
+pub const KEY_ENV: &str = "AOC_INPUTS_KEY";
+
+/// File holding the passphrase, used when [`KEY_ENV`] is not set.
+pub const KEY_FILE: &str = ".aoc-inputs-key";
+
+/// Identifies the file format, bumped if the layout or key derivation ever changes.
+const MAGIC: &[u8] = b"AOCINPUT2";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug)]
+pub enum Error {
+    MissingKey,
+    MissingInput,
+    Corrupted,
+    WrongKey,
+    IO(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingKey => write!(
+                f,
+                "no key found, set {KEY_ENV} or write a passphrase to \"{KEY_FILE}\""
+            ),
+            Error::MissingInput => write!(f, "input file is missing or empty"),
+            Error::Corrupted => write!(f, "encrypted file is not in the expected format"),
+            Error::WrongKey => write!(f, "decryption failed, is the key correct?"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// What happened to a single day when encrypting or decrypting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Written,
+    Unchanged,
+}
+
+/// The state of a day's input on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    pub plaintext: bool,
+    pub encrypted: bool,
+    /// Whether both files hold the same input, [`None`] if this could not be checked.
+    pub in_sync: Option<bool>,
+}
+
+pub fn get_plaintext_path(day: Day) -> PathBuf {
+    PathBuf::from(format!("data/inputs/{day}.txt"))
+}
+
+pub fn get_encrypted_path(day: Day) -> PathBuf {
+    PathBuf::from(format!("data/inputs/{day}.txt.enc"))
+}
+
+/// Reads the passphrase from [`KEY_ENV`] or [`KEY_FILE`].
+pub fn load_passphrase() -> Result<String, Error> {
+    let passphrase = match env::var(KEY_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => fs::read_to_string(KEY_FILE).map_err(|_| Error::MissingKey)?,
+    };
+
+    let passphrase = passphrase.trim();
+    if passphrase.is_empty() {
+        return Err(Error::MissingKey);
+    }
+
+    Ok(passphrase.to_string())
+}
+
+/// Derives the encryption key with the default Argon2id parameters, which take a noticeable
+/// amount of time and memory on purpose.
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .expect("the salt and key lengths are valid for argon2");
+    key
+}
+
+/// Encrypts `plaintext` under a fresh salt and nonce, both stored in front of the ciphertext.
+pub fn encrypt(passphrase: &str, plaintext: &str) -> Vec<u8> {
+    let mut salt = [0; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .expect("encrypting in memory does not fail");
+
+    [MAGIC, &salt, nonce.as_slice(), &ciphertext].concat()
+}
+
+pub fn decrypt(passphrase: &str, contents: &[u8]) -> Result<String, Error> {
+    let rest = contents.strip_prefix(MAGIC).ok_or(Error::Corrupted)?;
+    if rest.len() < SALT_LENGTH + NONCE_LENGTH {
+        return Err(Error::Corrupted);
+    }
+
+    let (salt, rest) = rest.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+    let plaintext = ChaCha20Poly1305::new(&derive_key(passphrase, salt))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::WrongKey)?;
+
+    String::from_utf8(plaintext).map_err(|_| Error::Corrupted)
+}
+
+/// Decrypts the stored input for `day` without writing it to disk.
+pub fn read_encrypted(day: Day) -> Result<String, Error> {
+    let contents = fs::read(get_encrypted_path(day)).map_err(|_| Error::MissingInput)?;
+    decrypt(&load_passphrase()?, &contents)
+}
+
+/// Encrypts the plaintext input for `day`. An existing encrypted file holding the same input is
+/// left untouched, as a fresh salt and nonce would otherwise change it on every run.
+pub fn encrypt_day(passphrase: &str, day: Day) -> Result<Outcome, Error> {
+    let plaintext = read_plaintext(day).ok_or(Error::MissingInput)?;
+
+    if let Ok(existing) = fs::read(get_encrypted_path(day)) {
+        if decrypt(passphrase, &existing).is_ok_and(|existing| existing == plaintext) {
+            return Ok(Outcome::Unchanged);
+        }
+    }
+
+    fs::write(get_encrypted_path(day), encrypt(passphrase, &plaintext))?;
+    Ok(Outcome::Written)
+}
+
+/// Restores the plaintext input for `day` from its encrypted copy.
+pub fn decrypt_day(passphrase: &str, day: Day) -> Result<Outcome, Error> {
+    let contents = fs::read(get_encrypted_path(day)).map_err(|_| Error::MissingInput)?;
+    let plaintext = decrypt(passphrase, &contents)?;
+
+    if read_plaintext(day).is_some_and(|existing| existing == plaintext) {
+        return Ok(Outcome::Unchanged);
+    }
+
+    fs::write(get_plaintext_path(day), plaintext)?;
+    Ok(Outcome::Written)
+}
+
+pub fn status(passphrase: Option<&str>, day: Day) -> Status {
+    let plaintext = read_plaintext(day);
+    let encrypted = fs::read(get_encrypted_path(day)).ok();
+
+    let in_sync = match (passphrase, &plaintext, &encrypted) {
+        (Some(passphrase), Some(plaintext), Some(encrypted)) => decrypt(passphrase, encrypted)
+            .ok()
+            .map(|decrypted| decrypted == *plaintext),
+        _ => None,
+    };
+
+    Status {
+        plaintext: plaintext.is_some(),
+        encrypted: encrypted.is_some(),
+        in_sync,
+    }
+}
+
+/// The plaintext input, treating an empty file (as created by `cargo scaffold`) as missing.
+fn read_plaintext(day: Day) -> Option<String> {
+    fs::read_to_string(get_plaintext_path(day))
+        .ok()
+        .filter(|input| !input.is_empty())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{decrypt, derive_key, encrypt, Error, MAGIC, SALT_LENGTH};
+
+    #[test]
+    fn round_trips() {
+        let encrypted = encrypt("correct horse battery staple", "3   4\n4   3\n");
+        assert_eq!(
+            decrypt("correct horse battery staple", &encrypted).unwrap(),
+            "3   4\n4   3\n"
+        );
+    }
+
+    #[test]
+    fn uses_fresh_salts_and_nonces() {
+        let (one, two) = (
+            encrypt("passphrase", "input"),
+            encrypt("passphrase", "input"),
+        );
+        let salt = |contents: &[u8]| contents[MAGIC.len()..MAGIC.len() + SALT_LENGTH].to_vec();
+        assert_ne!(salt(&one), salt(&two));
+        assert_ne!(one, two);
+    }
+
+    #[test]
+    fn salts_the_key() {
+        assert_ne!(
+            derive_key("passphrase", &[0; SALT_LENGTH]),
+            derive_key("passphrase", &[1; SALT_LENGTH])
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_key_and_garbage() {
+        let encrypted = encrypt("one", "input");
+        assert!(matches!(decrypt("two", &encrypted), Err(Error::WrongKey)));
+        assert!(matches!(decrypt("one", b"garbage"), Err(Error::Corrupted)));
+        assert!(matches!(
+            decrypt("one", &encrypted[..MAGIC.len() + SALT_LENGTH]),
+            Err(Error::Corrupted)
+        ));
+    }
+}
@@ -1,14 +1,26 @@
 use std::{env, fs};
 
+pub mod allocations;
 pub mod aoc_cli;
 pub mod commands;
+pub mod crosscheck;
+pub mod inputs;
 pub mod runner;
+pub mod scaling;
 
 pub use day::*;
 
+mod answers;
+mod cache;
 mod day;
+mod history;
+mod presets;
 mod readme_benchmarks;
+mod readme_stars;
+mod report;
 mod run_multi;
+mod samples;
+mod status;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -16,12 +28,21 @@ pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
 /// Helper function that reads a text file to a string.
+/// Inputs missing in plaintext are decrypted from their encrypted copy, see [`inputs`].
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+
+    match f {
+        Ok(contents) if !(contents.is_empty() && folder == "inputs") => contents,
+        _ if folder == "inputs" && inputs::get_encrypted_path(day).exists() => {
+            inputs::read_encrypted(day)
+                .unwrap_or_else(|e| panic!("could not decrypt input file: {e}"))
+        }
+        f => f.expect("could not open input file"),
+    }
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
@@ -39,19 +60,31 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// It can be followed by a list of `key = value` options:
+///  - `export = <fn>` registers a function that builds [`Frames`](crate::export::Frames) from the
+///    input, which are written to disk when the solution is run with `--export <format>`.
+///  - `reference_one = <fn>` and `reference_two = <fn>` register alternative implementations of a
+///    part that `cargo crosscheck` compares the solution against.
+///  - `parse = <fn>` registers the input parser. Errors it returns are printed before the parts
+///    run, and it is benched on its own when timing the solution so that the readme can report
+///    parse times.
+///  - `generate = <fn>` registers a generator of random inputs taking a size and a seed. It is
+///    used by `cargo crosscheck` and by `cargo time <day> --scale`, which times the solution on
+///    inputs of increasing size.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($day:expr, 1 $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::solution!(@impl $day, [part_one, 1] $(; $key = $value)*);
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+    ($day:expr, 2 $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::solution!(@impl $day, [part_two, 2] $(; $key = $value)*);
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    ($day:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2] $(; $key = $value)*);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(; $key:ident = $value:expr)*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +92,62 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(not(feature = "dhat-heap"))]
+        #[global_allocator]
+        static ALLOC: $crate::template::allocations::CountingAlloc =
+            $crate::template::allocations::CountingAlloc;
+
         fn main() {
             use $crate::template::runner::*;
+
+            if $crate::template::crosscheck::Crosscheck::is_requested() {
+                #[allow(unused_mut)]
+                let mut crosscheck = $crate::template::crosscheck::Crosscheck::new(DAY);
+                $( $crate::solution!(@crosscheck crosscheck, $key = $value); )*
+                crosscheck.run();
+                return;
+            }
+
+            if $crate::template::scaling::Scaling::is_requested() {
+                let mut scaling = $crate::template::scaling::Scaling::new(DAY);
+                $( scaling.add_part($part, $func); )*
+                $( $crate::solution!(@scaling scaling, $key = $value); )*
+                scaling.run();
+                return;
+            }
+
             let input = $crate::template::read_file("inputs", DAY);
+            report_threads();
+            $( $crate::solution!(@before &input, $key = $value); )*
             $( run_part($func, &input, DAY, $part); )*
+            $( $crate::solution!(@after &input, $key = $value); )*
         }
     };
+
+    (@crosscheck $crosscheck:ident, reference_one = $reference:expr) => {
+        $crosscheck.add_part(1, part_one, $reference);
+    };
+    (@crosscheck $crosscheck:ident, reference_two = $reference:expr) => {
+        $crosscheck.add_part(2, part_two, $reference);
+    };
+    (@crosscheck $crosscheck:ident, generate = $generator:expr) => {
+        $crosscheck.set_generator($generator);
+    };
+    (@crosscheck $crosscheck:ident, $key:ident = $value:expr) => {};
+
+    (@scaling $scaling:ident, generate = $generator:expr) => {
+        $scaling.set_generator($generator);
+    };
+    (@scaling $scaling:ident, $key:ident = $value:expr) => {};
+
+    (@before $input:expr, parse = $parse:expr) => {
+        check_parse($parse, $input);
+        run_parse($parse, $input, DAY);
+    };
+    (@before $input:expr, $key:ident = $value:expr) => {};
+
+    (@after $input:expr, export = $export:expr) => {
+        $crate::export::export_from_args($export, $input, DAY);
+    };
+    (@after $input:expr, $key:ident = $value:expr) => {};
 }
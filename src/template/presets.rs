@@ -0,0 +1,160 @@
+/// Scaffold templates: named presets in `templates/` and the placeholders substituted into them.
+use std::{fmt::Display, fs, io, path::Path};
+
+use crate::template::{aoc_cli, Day};
+
+static TEMPLATES_DIR: &str = "./templates";
+
+/// The preset used when `cargo scaffold` is called without `--template`.
+pub const DEFAULT_PRESET: &str = "default";
+
+/// The preset used for days with a single part, see [`Day::is_single_part`].
+pub const SINGLE_PART_PRESET: &str = "single-part";
+
+#[derive(Debug)]
+pub enum Error {
+    /// No preset with the given name, lists the presets that exist.
+    Missing {
+        name: String,
+        available: Vec<String>,
+    },
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Missing { name, available } => write!(
+                f,
+                "no template named `{name}` in \"{TEMPLATES_DIR}\", available: {}",
+                available.join(", ")
+            ),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// The values substituted into a template.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Placeholders {
+    pub year: Option<u16>,
+    pub title: Option<String>,
+    pub example_one: Option<String>,
+    pub example_two: Option<String>,
+}
+
+impl Placeholders {
+    /// Collects the placeholders of `day` from the configured year and the downloaded puzzle.
+    pub fn for_day(day: Day, example_one: Option<String>, example_two: Option<String>) -> Self {
+        Placeholders {
+            year: aoc_cli::get_year(),
+            title: fs::read_to_string(aoc_cli::get_puzzle_path(day))
+                .ok()
+                .and_then(|puzzle| puzzle_title(&puzzle)),
+            example_one,
+            example_two,
+        }
+    }
+}
+
+/// The names of all presets in `templates/`, sorted.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(TEMPLATES_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_suffix(".txt").map(String::from)
+        })
+        .collect();
+
+    names.sort();
+    names
+}
+
+pub fn load(name: &str) -> Result<String, Error> {
+    let path = Path::new(TEMPLATES_DIR).join(format!("{name}.txt"));
+
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::Missing {
+            name: name.into(),
+            available: available(),
+        },
+        _ => Error::IO(e),
+    })
+}
+
+/// Substitutes the placeholders of a template:
+///  - `%DAY_NUMBER%`: the day without padding.
+///  - `%YEAR%`: the configured `AOC_YEAR`.
+///  - `%TITLE%`: the puzzle title, if the puzzle was downloaded.
+///  - `%EXAMPLE_ONE%`, `%EXAMPLE_TWO%`: the expected example answers as `Some(..)`, or `None`.
+pub fn render(template: &str, day: Day, placeholders: &Placeholders) -> String {
+    let example = |answer: &Option<String>| {
+        answer
+            .as_ref()
+            .map_or_else(|| "None".into(), |answer| format!("Some({answer})"))
+    };
+
+    template
+        .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+        .replace(
+            "%YEAR%",
+            &placeholders
+                .year
+                .map_or_else(String::new, |year| year.to_string()),
+        )
+        .replace(
+            "%TITLE%",
+            placeholders
+                .title
+                .as_deref()
+                .unwrap_or("puzzle not downloaded yet"),
+        )
+        .replace("%EXAMPLE_ONE%", &example(&placeholders.example_one))
+        .replace("%EXAMPLE_TWO%", &example(&placeholders.example_two))
+}
+
+/// Extracts the title from a puzzle description saved by aoc-cli, which starts with a heading
+/// like `--- Day 1: Historian Hysteria ---`.
+pub fn puzzle_title(puzzle: &str) -> Option<String> {
+    puzzle.lines().find_map(|line| {
+        let heading = line.trim().trim_start_matches('\\');
+        let heading = heading.strip_prefix("--- Day ")?.strip_suffix("---")?;
+        let (_, title) = heading.split_once(':')?;
+        Some(title.trim().to_string())
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{puzzle_title, render, Placeholders};
+    use crate::day;
+
+    #[test]
+    fn extracts_puzzle_titles() {
+        let puzzle =
+            "\\--- Day 1: Historian Hysteria ---\n----------\n\nThe *Chief Historian* is...";
+        assert_eq!(puzzle_title(puzzle), Some("Historian Hysteria".into()));
+        assert_eq!(puzzle_title("no heading here"), None);
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "// %YEAR% %DAY_NUMBER%: %TITLE%\nsolution!(%DAY_NUMBER%);\n%EXAMPLE_ONE% %EXAMPLE_TWO%";
+        let placeholders = Placeholders {
+            year: Some(2024),
+            title: Some("Red-Nosed Reports".into()),
+            example_one: Some("11".into()),
+            example_two: None,
+        };
+
+        assert_eq!(
+            render(template, day!(2), &placeholders),
+            "// 2024 2: Red-Nosed Reports\nsolution!(2);\nSome(11) None"
+        );
+    }
+}
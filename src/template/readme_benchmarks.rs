@@ -1,8 +1,9 @@
 /// Module that updates the readme me with timing information.
+/// The columns and the lines around the table are configured through a [`TableLayout`].
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{env, fs, io, str::FromStr};
 
-use crate::template::timings::Timings;
+use crate::template::timings::{Timing, Timings};
 use crate::template::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
@@ -21,8 +22,8 @@ impl From<std::io::Error> for Error {
 }
 
 pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+    pub pos_start: usize,
+    pub pos_end: usize,
 }
 
 #[must_use]
@@ -30,8 +31,9 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Finds the table enclosed by two occurrences of `marker`, including the markers.
+pub fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -52,38 +54,209 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+/// An optional column of the benchmark table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Part1,
+    Part2,
+    Parse,
+    Total,
+    Samples,
+    Allocations,
+    Delta,
+    Bar,
+}
+
+impl FromStr for Column {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "part_1" => Ok(Column::Part1),
+            "part_2" => Ok(Column::Part2),
+            "parse" => Ok(Column::Parse),
+            "total" => Ok(Column::Total),
+            "samples" => Ok(Column::Samples),
+            "allocations" => Ok(Column::Allocations),
+            "delta" => Ok(Column::Delta),
+            "bar" => Ok(Column::Bar),
+            _ => Err(Error::Parser(format!("unknown benchmark column `{s}`."))),
+        }
+    }
+}
+
+impl Column {
+    fn title(self) -> &'static str {
+        match self {
+            Column::Part1 => "Part 1",
+            Column::Part2 => "Part 2",
+            Column::Parse => "Parse",
+            Column::Total => "Total",
+            Column::Samples => "Samples",
+            Column::Allocations => "Allocations",
+            Column::Delta => "Δ",
+            Column::Bar => "Relative",
+        }
+    }
+
+    fn cell(self, timing: &Timing, slowest_nanos: f64) -> String {
+        let code = |value: &Option<String>| match value {
+            Some(value) => format!("`{value}`"),
+            None => "`-`".into(),
+        };
+        let pair = |values: &[Option<u64>; 2]| {
+            let format = |value: &Option<u64>| value.map_or_else(|| "-".into(), |x| x.to_string());
+            if timing.single_part {
+                format(&values[0])
+            } else {
+                format!("{} / {}", format(&values[0]), format(&values[1]))
+            }
+        };
+
+        match self {
+            Column::Part1 => code(&timing.part_1),
+            Column::Part2 if timing.single_part && timing.part_2.is_none() => "n/a".into(),
+            Column::Part2 => code(&timing.part_2),
+            Column::Parse => code(&timing.parse),
+            Column::Total => format!("`{:.2}ms`", timing.total_nanos / 1_000_000_f64),
+            Column::Samples => pair(&timing.samples),
+            Column::Allocations => pair(&timing.allocations),
+            Column::Delta => match timing.previous_total_nanos {
+                Some(previous) if previous > 0_f64 => {
+                    format!("{:+.1}%", (timing.total_nanos / previous - 1_f64) * 100_f64)
+                }
+                _ => "-".into(),
+            },
+            Column::Bar => format!("`{}`", bar(timing.total_nanos / slowest_nanos, BAR_WIDTH)),
+        }
+    }
+}
+
+/// Width of the relative bars, in characters.
+const BAR_WIDTH: usize = 10;
+
+/// Renders `fraction` (between 0 and 1) as a bar of `width` characters, in eighths of a character.
+fn bar(fraction: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let eighths = (fraction.clamp(0_f64, 1_f64) * (width * 8) as f64).round() as usize;
+    // NOTE: keep a sliver visible for very fast days.
+    let eighths = if fraction > 0_f64 { eighths.max(1) } else { 0 };
+
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(PARTIAL[eighths % 8]);
+    }
+    format!("{bar:<width$}")
+}
+
+/// Which columns the benchmark table shows, and the templates for the lines around it.
+/// `{prefix}` is replaced by the heading prefix, `{total}` by the total time in milliseconds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableLayout {
+    pub columns: Vec<Column>,
+    pub header: String,
+    pub footer: String,
+}
+
+impl Default for TableLayout {
+    fn default() -> Self {
+        TableLayout {
+            columns: vec![Column::Part1, Column::Part2],
+            header: "{prefix} Benchmarks".into(),
+            footer: "**Total: {total}ms**".into(),
+        }
+    }
+}
+
+impl TableLayout {
+    /// Reads the layout from the `AOC_BENCH_COLUMNS`, `AOC_BENCH_HEADER` and `AOC_BENCH_FOOTER`
+    /// environment variables, usually set in the `[env]` section of `.cargo/config.toml`.
+    pub fn from_env() -> Result<Self, Error> {
+        let mut layout = TableLayout::default();
+
+        if let Ok(columns) = env::var("AOC_BENCH_COLUMNS") {
+            layout.columns = columns
+                .split(',')
+                .filter(|column| !column.trim().is_empty())
+                .map(Column::from_str)
+                .collect::<Result<_, _>>()?;
+        }
+        if let Ok(header) = env::var("AOC_BENCH_HEADER") {
+            layout.header = header;
+        }
+        if let Ok(footer) = env::var("AOC_BENCH_FOOTER") {
+            layout.footer = footer;
+        }
+
+        Ok(layout)
+    }
+}
+
+fn construct_table(
+    prefix: &str,
+    timings: Timings,
+    total_millis: f64,
+    layout: &TableLayout,
+) -> String {
+    let fill = |template: &str| {
+        template
+            .replace("{prefix}", prefix)
+            .replace("{total}", &format!("{total_millis:.2}"))
+    };
+
+    let titles: Vec<&str> = layout.columns.iter().map(|column| column.title()).collect();
+    let separators = vec![":---:"; layout.columns.len() + 1];
 
     let mut lines: Vec<String> = vec![
         MARKER.into(),
-        header,
+        fill(&layout.header),
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!("| Day | {} |", titles.join(" | ")),
+        format!("| {}  |", separators.join(" | ")),
     ];
 
-    for timing in timings.data {
+    let slowest_nanos = timings
+        .data
+        .iter()
+        .map(|timing| timing.total_nanos)
+        .fold(0_f64, f64::max);
+
+    for timing in &timings.data {
         let path = get_path_for_bin(timing.day);
+        let cells: Vec<String> = layout
+            .columns
+            .iter()
+            .map(|column| column.cell(timing, slowest_nanos))
+            .collect();
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | {} |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            cells.join(" | ")
         ));
     }
 
     lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
+    lines.push(fill(&layout.footer));
     lines.push(MARKER.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+fn update_content(
+    s: &mut String,
+    timings: Timings,
+    total_millis: f64,
+    layout: &TableLayout,
+) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table("##", timings, total_millis, layout);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
@@ -92,14 +265,19 @@ pub fn update(timings: Timings) -> Result<(), Error> {
     let path = "README.md";
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+    update_content(
+        &mut readme,
+        timings,
+        total_millis,
+        &TableLayout::from_env()?,
+    )?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, MARKER};
+    use super::{bar, update_content, Column, TableLayout, MARKER};
     use crate::{day, template::timings::Timing, template::timings::Timings};
 
     fn get_mock_timings() -> Timings {
@@ -110,18 +288,21 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -131,28 +312,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +341,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, &TableLayout::default()).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -180,4 +361,70 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_configured_benchmarks() {
+        let mut timings = get_mock_timings();
+        timings.data[0].parse = Some("1ms".into());
+        timings.data[0].samples = [Some(100), Some(50)];
+        timings.data[0].allocations = [Some(3), None];
+        timings.data[0].previous_total_nanos = Some(2e+10);
+
+        let layout = TableLayout {
+            columns: vec![
+                Column::Parse,
+                Column::Samples,
+                Column::Allocations,
+                Column::Delta,
+                Column::Bar,
+            ],
+            header: "{prefix}# ⏱️ {total}ms".into(),
+            footer: String::new(),
+        };
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0, &layout).unwrap();
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(lines[1], "### ⏱️ 190.00ms");
+        assert_eq!(
+            lines[3],
+            "| Day | Parse | Samples | Allocations | Δ | Relative |"
+        );
+        assert_eq!(
+            lines[5],
+            "| [Day 1](./src/bin/01.rs) | `1ms` | 100 / 50 | 3 / - | +50.0% | `███▍      ` |"
+        );
+        assert_eq!(
+            lines[7],
+            "| [Day 4](./src/bin/04.rs) | `-` | - / - | - / - | - | `██████████` |"
+        );
+    }
+
+    #[test]
+    fn format_single_part_benchmarks() {
+        let mut timings = get_mock_timings();
+        timings.data.push(Timing {
+            part_1: Some("5ms".into()),
+            samples: [Some(10), None],
+            total_nanos: 5e+6,
+            ..Timing::new(day!(25))
+        });
+
+        let layout = TableLayout {
+            columns: vec![Column::Part1, Column::Part2, Column::Samples],
+            ..TableLayout::default()
+        };
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0, &layout).unwrap();
+        assert!(s.contains("| [Day 25](./src/bin/25.rs) | `5ms` | n/a | 10 |"));
+    }
+
+    #[test]
+    fn renders_bars() {
+        assert_eq!(bar(1.0, 4), "████");
+        assert_eq!(bar(0.5, 4), "██  ");
+        assert_eq!(bar(0.0, 4), "    ");
+        assert_eq!(bar(0.001, 4), "▏   ");
+    }
 }
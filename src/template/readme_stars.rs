@@ -0,0 +1,149 @@
+/// Module that updates the readme with the stars earned so far, replacing the table
+/// previously maintained by the `aoc-readme-stars` action.
+use std::fs;
+
+use crate::template::all_days;
+use crate::template::answers::{Answer, Answers};
+use crate::template::aoc_cli::get_year;
+use crate::template::readme_benchmarks::{get_path_for_bin, locate_table, Error};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+fn construct_table(answers: &Answers, year: Option<u16>) -> String {
+    let header = match year {
+        Some(year) => format!("## {year} Results"),
+        None => "## Results".into(),
+    };
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 | Solution |".into(),
+        "| :---: | :---: | :---: | :---: |".into(),
+    ];
+
+    for answer in answers.data.iter().filter(|answer| answer.stars() > 0) {
+        let day = answer.day.into_inner();
+        let title = match year {
+            Some(year) => format!("[Day {day}](https://adventofcode.com/{year}/day/{day})"),
+            None => format!("Day {day}"),
+        };
+        let star = |part: u8| {
+            if answer.part(part).is_some() || (part == 2 && has_free_star(answers, answer)) {
+                "⭐"
+            } else {
+                ""
+            }
+        };
+
+        lines.push(format!(
+            "| {title} | {} | {} | [{}.rs]({}) |",
+            star(1),
+            star(2),
+            answer.day,
+            get_path_for_bin(answer.day)
+        ));
+    }
+
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+/// The second star of a single-part day is awarded once its first part and every other
+/// puzzle is solved.
+fn has_free_star(answers: &Answers, answer: &Answer) -> bool {
+    let other_stars: usize = answers
+        .data
+        .iter()
+        .filter(|other| other.day != answer.day)
+        .map(Answer::stars)
+        .sum();
+
+    answer.day.is_single_part()
+        && answer.part_1.is_some()
+        && other_stars == 2 * (all_days().count() - 1)
+}
+
+fn update_content(s: &mut String, answers: &Answers, year: Option<u16>) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table(answers, year);
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+pub fn update(answers: &Answers) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, answers, get_year())?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, MARKER};
+    use crate::{day, template::answers::Answers, template::Day};
+
+    fn get_mock_answers() -> Answers {
+        Answers::default()
+            .record(day!(1), 1, Some("11".into()))
+            .record(day!(1), 2, None)
+            .record(day!(3), 1, None)
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let mut s = "# readme".to_string();
+        update_content(&mut s, &get_mock_answers(), Some(2024)).unwrap();
+    }
+
+    #[test]
+    fn updates_existing_stars() {
+        let mut s = format!("foo\n{}\n| old |\n{}\nbar", MARKER, MARKER);
+        update_content(&mut s, &get_mock_answers(), Some(2024)).unwrap();
+        update_content(&mut s, &get_mock_answers(), Some(2024)).unwrap();
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.matches("| old |").count(), 0);
+    }
+
+    #[test]
+    fn format_stars() {
+        let mut s = format!("foo\n{}{}\nbar", MARKER, MARKER);
+        update_content(&mut s, &get_mock_answers(), Some(2024)).unwrap();
+        let expected = [
+            "foo",
+            "<!--- advent_readme_stars table --->",
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 | Solution |",
+            "| :---: | :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ | [01.rs](./src/bin/01.rs) |",
+            "| [Day 3](https://adventofcode.com/2024/day/3) | ⭐ |  | [03.rs](./src/bin/03.rs) |",
+            "<!--- advent_readme_stars table --->",
+            "bar",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn awards_free_star_of_single_part_days() {
+        let answers = (1..=24)
+            .filter_map(Day::new)
+            .fold(Answers::default(), |answers, day| {
+                answers.record(day, 1, None).record(day, 2, None)
+            })
+            .record(day!(25), 1, None);
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, &answers, None).unwrap();
+        assert!(s.contains("| Day 25 | ⭐ | ⭐ |"));
+
+        let mut s = format!("{}{}", MARKER, MARKER);
+        update_content(&mut s, &get_mock_answers().record(day!(25), 1, None), None).unwrap();
+        assert!(s.contains("| Day 25 | ⭐ |  |"));
+    }
+}
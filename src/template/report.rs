@@ -0,0 +1,152 @@
+/// Builds a self-contained HTML performance report from the stored timings, their history and the
+/// raw benchmark samples. Everything, including styles and scripts, is inlined so that the report
+/// works offline.
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+
+use crate::template::aoc_cli;
+use crate::template::history::History;
+use crate::template::run_multi::child_commands::parse_nanos;
+use crate::template::samples::Samples;
+use crate::template::timings::{Timing, Timings};
+
+const REPORT_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/report.html"));
+
+pub fn render(timings: &Timings, history: &History, samples: &[Samples]) -> String {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert(
+        "year".into(),
+        aoc_cli::get_year().map_or(JsonValue::Null, |year| JsonValue::Number(year.into())),
+    );
+    map.insert(
+        "days".into(),
+        JsonValue::Array(timings.data.iter().map(day_to_json).collect()),
+    );
+    map.insert(
+        "history".into(),
+        JsonValue::Array(
+            history
+                .runs
+                .iter()
+                .map(|run| {
+                    let mut map: HashMap<String, JsonValue> = HashMap::new();
+                    #[allow(clippy::cast_precision_loss)]
+                    map.insert("timestamp".into(), JsonValue::Number(run.timestamp as f64));
+                    map.insert(
+                        "days".into(),
+                        JsonValue::Array(run.timings.data.iter().map(day_to_json).collect()),
+                    );
+                    JsonValue::Object(map)
+                })
+                .collect(),
+        ),
+    );
+    map.insert(
+        "samples".into(),
+        JsonValue::Array(samples.iter().map(samples_to_json).collect()),
+    );
+
+    let data = JsonValue::Object(map).stringify().unwrap_or_default();
+    REPORT_TEMPLATE.replace("%REPORT_DATA%", &escape_script(&data))
+}
+
+/// Escapes JSON for embedding in a `<script>` element, which would otherwise be closed by any
+/// `</script>` occurring in a string.
+fn escape_script(json: &str) -> String {
+    json.replace("</", "<\\/")
+}
+
+fn optional_nanos(timing: Option<&String>) -> JsonValue {
+    timing
+        .and_then(|t| parse_nanos(t))
+        .map_or(JsonValue::Null, JsonValue::Number)
+}
+
+fn optional_count(count: Option<u64>) -> JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    count.map_or(JsonValue::Null, |x| JsonValue::Number(x as f64))
+}
+
+fn day_to_json(timing: &Timing) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert("day".into(), JsonValue::String(timing.day.to_string()));
+    map.insert("part_1".into(), optional_nanos(timing.part_1.as_ref()));
+    map.insert("part_2".into(), optional_nanos(timing.part_2.as_ref()));
+    map.insert("parse".into(), optional_nanos(timing.parse.as_ref()));
+    map.insert("total".into(), JsonValue::Number(timing.total_nanos));
+    map.insert(
+        "samples".into(),
+        JsonValue::Array(timing.samples.map(optional_count).into()),
+    );
+    map.insert(
+        "allocations".into(),
+        JsonValue::Array(timing.allocations.map(optional_count).into()),
+    );
+
+    JsonValue::Object(map)
+}
+
+fn samples_to_json(samples: &Samples) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert("day".into(), JsonValue::String(samples.day.to_string()));
+    map.insert("label".into(), JsonValue::String(samples.label.clone()));
+    map.insert(
+        "nanos".into(),
+        JsonValue::Array(
+            samples
+                .nanos
+                .iter()
+                .copied()
+                .map(JsonValue::Number)
+                .collect(),
+        ),
+    );
+
+    JsonValue::Object(map)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{escape_script, render};
+    use crate::day;
+    use crate::template::history::History;
+    use crate::template::samples::Samples;
+    use crate::template::timings::{Timing, Timings};
+
+    #[test]
+    fn escapes_closing_tags() {
+        assert_eq!(
+            escape_script(r#"{"a":"</script>"}"#),
+            r#"{"a":"<\/script>"}"#
+        );
+    }
+
+    #[test]
+    fn embeds_report_data() {
+        let timings = Timings {
+            data: vec![Timing {
+                part_1: Some("1.5µs".into()),
+                total_nanos: 1500_f64,
+                samples: [Some(10), None],
+                ..Timing::new(day!(1))
+            }],
+        };
+        let samples = [Samples {
+            day: day!(1),
+            label: "1".into(),
+            nanos: vec![1000_f64, 2000_f64],
+        }];
+
+        let html = render(&timings, &History::default().push(&timings), &samples);
+
+        assert!(!html.contains("%REPORT_DATA%"));
+        assert!(html.contains(r#""part_1":1500"#));
+        assert!(html.contains(r#""nanos":[1000,2000]"#));
+        assert!(!html.contains("<script src") && !html.contains("<link"));
+    }
+}
@@ -4,11 +4,24 @@ use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
+    cache::{self, Cache},
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Runs the solutions of `days_to_run`, built with the `parallel` feature if `is_parallel` is set.
+/// With `store_samples`, timed runs also write their raw benchmark samples to disk.
+/// With a `cache`, days whose sources and input did not change since their last successful run
+/// are not run again, their cached output is used instead.
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_parallel: bool,
+    is_timed: bool,
+    store_samples: bool,
+    mut cache: Option<&mut Cache>,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let shared_hash = cache.as_ref().map(|_| cache::shared_hash());
 
     let mut need_space = false;
 
@@ -21,15 +34,53 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             }
             need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            let hash = shared_hash
+                .as_ref()
+                .map(|shared| cache::day_hash(day, shared, is_release, is_parallel));
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let cached = cache
+                .as_deref()
+                .zip(hash.as_deref())
+                .and_then(|(cache, hash)| cache.get(day, hash))
+                .map(<[String]>::to_vec);
+
+            let is_cached = cached.is_some();
+
+            // NOTE: only the output of successful runs is cached.
+            let (output, success) = if let Some(output) = cached {
+                println!("{ANSI_BOLD}Day {day}{ANSI_RESET} {ANSI_ITALIC}(cached){ANSI_RESET}");
+                println!("------");
+                output.iter().for_each(|line| println!("{line}"));
+                (output, true)
+            } else {
+                println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+                println!("------");
+
+                let (output, success) = child_commands::run_solution(
+                    day,
+                    is_timed,
+                    is_release,
+                    is_parallel,
+                    store_samples,
+                )
+                .unwrap();
+
+                if let Some(cache) = cache.as_deref_mut() {
+                    match hash {
+                        Some(hash) if success && !output.is_empty() => {
+                            cache.insert(day, hash, output.clone());
+                        }
+                        _ => cache.remove(day),
+                    }
+                }
+                (output, success)
+            };
 
             if output.is_empty() {
                 println!("Not solved.");
             } else {
-                let val = child_commands::parse_exec_time(&output, day);
+                let mut val = child_commands::parse_exec_time(&output, day, success);
+                val.cached = is_cached;
                 timings.push(val);
             }
         });
@@ -68,7 +119,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -76,11 +127,17 @@ pub mod child_commands {
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, returning its output and whether it succeeded.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        is_parallel: bool,
+        store_samples: bool,
+    ) -> Result<(Vec<String>, bool), Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok((vec![], false));
         }
 
         let day_padded = day.to_string();
@@ -90,10 +147,18 @@ pub mod child_commands {
             args.push("--release");
         }
 
+        if is_parallel {
+            args.extend(["--features", "parallel"]);
+        }
+
         if is_timed {
             // mirror `--time` flag to child invocations.
             args.push("--");
             args.push("--time");
+
+            if store_samples {
+                args.push("--store-samples");
+            }
         }
 
         // spawn child command with piped stdout/stderr.
@@ -123,18 +188,27 @@ pub mod child_commands {
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+        let status = cmd.wait()?;
 
-        Ok(output)
+        Ok((output, status.success()))
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+    /// Extracts the printed result of `part` from the output of a solution.
+    /// Multi-line results are not supported and yield [`None`].
+    pub fn parse_answer(output: &[String], part: u8) -> Option<String> {
+        let line = output
+            .iter()
+            .find(|l| l.starts_with(&format!("Part {part}: ")))?;
+
+        let answer = line.split_once(ANSI_BOLD)?.1.split_once(ANSI_RESET)?.0;
+
+        Some(answer.to_string())
+    }
+
+    /// Collects the timings printed by a solution. `success` is whether its process exited
+    /// successfully, a crashed run is never taken for a single-part solution.
+    pub fn parse_exec_time(output: &[String], day: Day, success: bool) -> super::Timing {
+        let mut timings = super::Timing::new(day);
 
         output
             .iter()
@@ -149,21 +223,61 @@ pub mod child_commands {
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing_str, nanos, parse_counts(l)))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
+            .for_each(|(part, timing_str, nanos, (samples, allocations))| {
+                // the parser is benched separately, its time is already part of both parts.
+                if part.contains("Parse") {
+                    timings.parse = Some(timing_str.into());
+                    return;
+                }
+
+                let index = if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    0
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
-                }
+                    1
+                } else {
+                    return;
+                };
 
+                timings.samples[index] = samples;
+                timings.allocations[index] = allocations;
                 timings.total_nanos += nanos;
             });
 
+        // solutions registered with `solution!(day, 1)` do not print a second part at all, but
+        // neither do solutions that panic in their second part.
+        let has_part = |part: &str| output.iter().any(|l| l.starts_with(part));
+        if success && has_part("Part 1") && !has_part("Part 2") {
+            timings.single_part = true;
+        }
+
         timings
     }
 
+    /// Parses the sample count and the optional allocation count after the timing,
+    /// e.g. `(74.13ns @ 100 samples) [12 allocations]`.
+    fn parse_counts(line: &str) -> (Option<u64>, Option<u64>) {
+        let Some((timing, rest)) = line.rsplit_once(" samples)") else {
+            return (None, None);
+        };
+
+        let samples = timing
+            .rsplit('@')
+            .next()
+            .and_then(|x| x.trim().parse().ok());
+
+        let allocations = rest
+            .trim()
+            .strip_prefix('[')
+            .and_then(|x| x.strip_suffix(" allocations]"))
+            .and_then(|x| x.parse().ok());
+
+        (samples, allocations)
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,19 +288,22 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
+        Some((str_timing, parse_nanos(str_timing)?))
+    }
+
+    /// Converts a formatted [`std::time::Duration`], e.g. `74.13µs`, to nanoseconds.
+    pub fn parse_nanos(timing: &str) -> Option<f64> {
+        match timing {
             s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
             s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
             s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
             s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        }
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,7 +322,7 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_answer, parse_exec_time};
 
         use crate::day;
 
@@ -218,6 +335,7 @@ pub mod child_commands {
                     "".into(),
                 ],
                 day!(1),
+                true,
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
             assert_eq!(res.part_1.unwrap(), "74.13ns");
@@ -233,12 +351,61 @@ pub mod child_commands {
                     "".into(),
                 ],
                 day!(1),
+                true,
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
             assert_eq!(res.part_1.unwrap(), "2s");
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn parses_counts_and_parse_time() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: (1.5µs @ 2000 samples) [3 allocations]".into(),
+                    "Part 1: 42 (10ns @ 100 samples) [12 allocations]".into(),
+                    "Part 2: 7 (20ns @ 50 samples)".into(),
+                ],
+                day!(1),
+                true,
+            );
+            assert_approx_eq!(res.total_nanos, 30_f64);
+            assert_eq!(res.parse.unwrap(), "1.5µs");
+            assert_eq!(res.samples, [Some(100), Some(50)]);
+            assert_eq!(res.allocations, [Some(12), None]);
+        }
+
+        #[test]
+        fn detects_single_part_solutions() {
+            let output = ["Part 1: 3 (10ns @ 100 samples)".to_string()];
+            let res = parse_exec_time(&output, day!(1), true);
+            assert_eq!(res.single_part, true);
+
+            // a second part that crashed prints nothing either.
+            let res = parse_exec_time(&output, day!(1), false);
+            assert_eq!(res.single_part, false);
+
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 3 (10ns @ 100 samples)".into(),
+                    "Part 2: ✖        ".into(),
+                ],
+                day!(1),
+                true,
+            );
+            assert_eq!(res.single_part, false);
+        }
+
+        #[test]
+        fn parses_answers() {
+            let output = [
+                "Part 1: \u{1b}[1m42\u{1b}[0m (10ns @ 100 samples)".to_string(),
+                "Part 2: ✖        ".to_string(),
+            ];
+            assert_eq!(parse_answer(&output, 1), Some("42".into()));
+            assert_eq!(parse_answer(&output, 2), None);
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
@@ -248,6 +415,7 @@ pub mod child_commands {
                     "".into(),
                 ],
                 day!(1),
+                true,
             );
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
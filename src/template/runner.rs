@@ -2,57 +2,125 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::answers::Answers;
+use crate::template::aoc_cli::Submission;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{allocations, aoc_cli, readme_stars, samples, Day, ANSI_ITALIC, ANSI_RESET};
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
+    let (result, measurement) =
         run_timed(func, input, |result| print_result(result, &part_str, ""));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result, &part_str, &format_duration(&measurement));
+    store_samples(day, &part.to_string(), &measurement.timers);
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Report how many threads parallel solutions use, if the `parallel` feature is enabled.
+pub fn report_threads() {
+    #[cfg(feature = "parallel")]
+    {
+        let threads = rayon::current_num_threads();
+        let plural = if threads == 1 { "" } else { "s" };
+        println!("{ANSI_ITALIC}Running on {threads} thread{plural}{ANSI_RESET}");
+    }
+}
+
+/// The result of an input parser, which may have rejected the input.
+pub trait Parsed {
+    /// Describes why the input was rejected, [`None`] if it was parsed.
+    fn error(&self) -> Option<String>;
+}
+
+impl<T, E: Display> Parsed for Result<T, E> {
+    fn error(&self) -> Option<String> {
+        self.as_ref().err().map(ToString::to_string)
+    }
+}
+
+/// Parsers of inputs that cannot be malformed return their value directly.
+impl<T> Parsed for Vec<T> {
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Runs the input parser of a solution once and reports why it rejected the input, if it did.
+/// The parts only see that parsing failed, so this is where the error is surfaced.
+pub fn check_parse<R: Parsed>(func: impl Fn(&str) -> R, input: &str) {
+    if let Some(error) = func(input).error() {
+        eprintln!("{ANSI_BOLD}Invalid input:{ANSI_RESET} {error}");
+    }
+}
+
+/// Bench the input parser of a solution on its own. Only runs when timing solutions.
+pub fn run_parse<R>(func: impl Fn(&str) -> R, input: &str, day: Day) {
+    if !env::args().any(|x| x == "--time") {
+        return;
+    }
+
+    print!("Parse:");
+    let (_, measurement) = run_timed(func, input, |_| {});
+    println!("\rParse:{}", format_duration(&measurement));
+    store_samples(day, "parse", &measurement.timers);
+}
+
+/// The timing of a function: its average duration and how it was obtained.
+struct Measurement {
+    duration: Duration,
+    samples: u128,
+    allocations: Option<u64>,
+    /// The duration of every benchmark iteration, empty if the function was not benched.
+    timers: Vec<Duration>,
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
-    input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+fn run_timed<I: Clone, T>(func: impl Fn(I) -> T, input: I, hook: impl Fn(&T)) -> (T, Measurement) {
     let timer = Instant::now();
-    let result = {
+    let (result, allocations) = {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        allocations::count(|| func(input))
     };
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let (duration, samples, timers) = if std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        (base_time, 1, vec![])
     };
 
-    (result, run.0, run.1)
+    (
+        result,
+        Measurement {
+            duration,
+            samples,
+            allocations,
+            timers,
+        },
+    )
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+) -> (Duration, u128, Vec<Duration>) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
@@ -75,6 +143,7 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         #[allow(clippy::cast_possible_truncation)]
         Duration::from_nanos(average_duration(&timers) as u64),
         bench_iterations,
+        timers,
     )
 }
 
@@ -86,9 +155,27 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
+/// Write the raw benchmark samples to disk when `cargo time --store` asked for them.
+/// They are used by `cargo bench-report` to plot the distribution of timings.
+fn store_samples(day: Day, label: &str, timers: &[Duration]) {
+    if timers.is_empty() || !env::args().any(|x| x == "--store-samples") {
+        return;
+    }
+
+    if let Err(e) = samples::store(day, label, timers) {
+        eprintln!("Failed to store benchmark samples: {e}");
+    }
+}
+
+fn format_duration(measurement: &Measurement) -> String {
+    let Measurement {
+        duration, samples, ..
+    } = measurement;
+
+    if *samples == 1 {
         format!(" ({duration:.1?})")
+    } else if let Some(allocations) = measurement.allocations {
+        format!(" ({duration:.1?} @ {samples} samples) [{allocations} allocations]")
     } else {
         format!(" ({duration:.1?} @ {samples} samples)")
     }
@@ -132,11 +219,13 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
+///
+/// Accepted answers are recorded in `data/answers.json` and the readme stars are refreshed.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<Submission, aoc_cli::AocCommandError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -165,5 +254,21 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let submission = aoc_cli::submit(day, part, &result.to_string());
+
+    let answer = match submission {
+        Ok(Submission::Correct) => Some(Some(result.to_string())),
+        // NOTE: the puzzle does not tell whether this answer was the accepted one.
+        Ok(Submission::AlreadySolved) => Some(None),
+        _ => None,
+    };
+
+    if let Some(answer) = answer {
+        let answers = Answers::read_from_file().record(day, part, answer);
+        if answers.store_file().is_err() || readme_stars::update(&answers).is_err() {
+            eprintln!("Failed to record the solved part.");
+        }
+    }
+
+    Some(submission)
 }
@@ -0,0 +1,58 @@
+/// Raw benchmark samples, stored per day and part in `data/samples` by `cargo time --store`.
+use std::{fs, io, path::Path, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static SAMPLES_DIR: &str = "./data/samples";
+
+/// The samples of one benched function: a part number or `parse`, and a duration in nanoseconds
+/// for every iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Samples {
+    pub day: Day,
+    pub label: String,
+    pub nanos: Vec<f64>,
+}
+
+pub fn store(day: Day, label: &str, timers: &[Duration]) -> io::Result<()> {
+    fs::create_dir_all(SAMPLES_DIR)?;
+
+    #[allow(clippy::cast_precision_loss)]
+    let json = JsonValue::Array(
+        timers
+            .iter()
+            .map(|timer| JsonValue::Number(timer.as_nanos() as f64))
+            .collect(),
+    );
+
+    let mut file = fs::File::create(Path::new(SAMPLES_DIR).join(format!("{day}-{label}.json")))?;
+    json.format_to(&mut file)
+}
+
+/// Reads every stored set of samples, sorted by day and label. Unreadable files are skipped.
+pub fn read_all() -> Vec<Samples> {
+    let mut all: Vec<Samples> = fs::read_dir(SAMPLES_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (day, label) = name.strip_suffix(".json")?.split_once('-')?;
+            let json = JsonValue::from_str(&fs::read_to_string(entry.path()).ok()?).ok()?;
+
+            Some(Samples {
+                day: day.parse().ok()?,
+                label: label.into(),
+                nanos: json
+                    .get::<Vec<JsonValue>>()?
+                    .iter()
+                    .filter_map(|x| x.get::<f64>().copied())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    all.sort_by(|a, b| (a.day, &a.label).cmp(&(b.day, &b.label)));
+    all
+}
@@ -0,0 +1,244 @@
+/// Runs solution parts on generated inputs of increasing size and estimates their complexity.
+use std::fmt::Display;
+use std::hint::black_box;
+use std::io::{stdout, Write};
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use crate::template::crosscheck::Generator;
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Sizes are doubled until a single run takes longer than this.
+const MAX_RUN_TIME: Duration = Duration::from_secs(1);
+
+/// Stop early if the growth so far predicts the next size would run longer than this.
+const MAX_PREDICTED_TIME: Duration = Duration::from_secs(4);
+
+/// Stop early if the growth so far predicts the next input would be larger than this.
+const MAX_BYTES: usize = 1 << 23;
+
+/// Upper bound on the size parameter, in case a part stays fast forever.
+const MAX_SIZE: usize = 1 << 16;
+
+/// Short runs are repeated until they add up to at least this much time.
+const MIN_SAMPLE_TIME: Duration = Duration::from_millis(20);
+
+/// Runs faster than this are dominated by noise and ignored when fitting the curve.
+const MIN_FIT_TIME: Duration = Duration::from_micros(10);
+
+type PartFn = Box<dyn Fn(&str)>;
+
+struct ScaledPart {
+    part: u8,
+    solution: PartFn,
+}
+
+/// A single measurement: the size parameter, the generated input length and the run time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub size: usize,
+    pub bytes: usize,
+    pub duration: Duration,
+}
+
+pub struct Scaling {
+    day: Day,
+    parts: Vec<ScaledPart>,
+    generator: Option<Generator>,
+}
+
+impl Scaling {
+    pub fn new(day: Day) -> Self {
+        Self {
+            day,
+            parts: vec![],
+            generator: None,
+        }
+    }
+
+    pub fn add_part<T: Display>(
+        &mut self,
+        part: u8,
+        solution: impl Fn(&str) -> Option<T> + 'static,
+    ) {
+        self.parts.push(ScaledPart {
+            part,
+            solution: Box::new(move |input| {
+                black_box(solution(black_box(input)));
+            }),
+        });
+    }
+
+    pub fn set_generator(&mut self, generator: Generator) {
+        self.generator = Some(generator);
+    }
+
+    /// Whether the solution was invoked with `--scale`.
+    pub fn is_requested() -> bool {
+        env::args().any(|x| x == "--scale")
+    }
+
+    /// Time every part on growing inputs and print the measurements with the fitted exponent.
+    pub fn run(&self) {
+        let Some(generator) = self.generator else {
+            eprintln!(
+                "Day {} has no input generator. Register one with `solution!({}, generate = ...)`.",
+                self.day,
+                self.day.into_inner()
+            );
+            process::exit(1);
+        };
+
+        for scaled in &self.parts {
+            println!("{ANSI_BOLD}Part {}{ANSI_RESET}", scaled.part);
+            println!("{:>8} {:>10} {:>12}", "size", "bytes", "time");
+
+            let samples = measure(scaled, generator);
+
+            match fit_exponent(&samples) {
+                Some(exponent) => println!(
+                    "Part {}: ~O(n^{exponent:.2}) {ANSI_ITALIC}(n = input bytes){ANSI_RESET}",
+                    scaled.part
+                ),
+                None => println!(
+                    "Part {}: {ANSI_ITALIC}too fast to estimate a curve{ANSI_RESET}",
+                    scaled.part
+                ),
+            }
+            println!();
+        }
+    }
+}
+
+fn measure(scaled: &ScaledPart, generator: Generator) -> Vec<Sample> {
+    let mut samples = vec![];
+    let mut size = 1;
+
+    while size <= MAX_SIZE {
+        let input = generator(size, size as u64);
+        let duration = time_run(&scaled.solution, &input);
+        let sample = Sample {
+            size,
+            bytes: input.len(),
+            duration,
+        };
+
+        println!(
+            "{:>8} {:>10} {:>12}",
+            sample.size,
+            sample.bytes,
+            format!("{:.1?}", sample.duration)
+        );
+        let _ = stdout().flush();
+
+        let stop = duration > MAX_RUN_TIME || next_too_large(samples.last(), &sample);
+        samples.push(sample);
+
+        if stop {
+            break;
+        }
+        size *= 2;
+    }
+
+    samples
+}
+
+/// Extrapolates the growth between the last two samples to the next size.
+fn next_too_large(previous: Option<&Sample>, current: &Sample) -> bool {
+    let Some(previous) = previous else {
+        return false;
+    };
+
+    let time_growth = current.duration.as_secs_f64() / previous.duration.as_secs_f64().max(1e-9);
+    let bytes_growth = current.bytes as f64 / previous.bytes.max(1) as f64;
+
+    current.duration.as_secs_f64() * time_growth > MAX_PREDICTED_TIME.as_secs_f64()
+        || current.bytes as f64 * bytes_growth > MAX_BYTES as f64
+}
+
+/// Average duration of `func` on `input`, repeating short runs to reduce noise.
+fn time_run(func: &PartFn, input: &str) -> Duration {
+    let timer = Instant::now();
+    func(input);
+    let first = timer.elapsed();
+
+    if first >= MIN_SAMPLE_TIME {
+        return first;
+    }
+
+    let mut runs = 0;
+    let timer = Instant::now();
+    while timer.elapsed() < MIN_SAMPLE_TIME {
+        func(input);
+        runs += 1;
+    }
+    timer.elapsed() / runs
+}
+
+/// Least-squares slope of `log(time)` against `log(bytes)` over the larger half of the samples,
+/// where constant setup costs no longer dominate. Runs too short to be reliable are ignored.
+/// A slope of 1 means linear behaviour, 2 quadratic and so on.
+pub fn fit_exponent(samples: &[Sample]) -> Option<f64> {
+    let mut points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|sample| sample.duration >= MIN_FIT_TIME && sample.bytes > 0)
+        .map(|sample| {
+            (
+                (sample.bytes as f64).ln(),
+                sample.duration.as_secs_f64().ln(),
+            )
+        })
+        .collect();
+
+    if points.len() < 2 {
+        return None;
+    }
+    points.drain(..(points.len() - 1) / 2);
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{fit_exponent, Sample};
+
+    fn samples(f: impl Fn(u64) -> u64) -> Vec<Sample> {
+        (4..10)
+            .map(|exponent| {
+                let bytes = 1 << exponent;
+                Sample {
+                    size: bytes,
+                    bytes,
+                    duration: Duration::from_micros(f(bytes as u64)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fits_linear_and_quadratic_curves() {
+        let linear = fit_exponent(&samples(|n| 10 * n)).unwrap();
+        let quadratic = fit_exponent(&samples(|n| 10 * n * n)).unwrap();
+        assert!((linear - 1.0).abs() < 0.01, "{linear}");
+        assert!((quadratic - 2.0).abs() < 0.01, "{quadratic}");
+    }
+
+    #[test]
+    fn ignores_noisy_samples() {
+        let fast = samples(|_| 1);
+        assert_eq!(fit_exponent(&fast), None);
+    }
+}
@@ -0,0 +1,205 @@
+/// An overview of where the season stands, day by day.
+use std::{collections::HashMap, fs, path::Path};
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::history::History;
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::{inputs, Day};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Puzzles unlock at midnight EST, which is 05:00 UTC.
+const UNLOCK_UTC_OFFSET: i64 = 5 * 3600;
+
+/// The most recently stored benchmark of a day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Benchmark {
+    pub total_nanos: f64,
+    /// When the benchmark was stored, if it is part of the history.
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayStatus {
+    pub day: Day,
+    pub scaffolded: bool,
+    /// A plaintext or encrypted input exists.
+    pub input: bool,
+    pub example: bool,
+    /// Whether `cargo test` passes for the day, [`None`] if it was not run.
+    pub tests: Option<bool>,
+    pub solved: usize,
+    /// Number of parts with a recorded answer.
+    pub answers: usize,
+    pub benchmark: Option<Benchmark>,
+    /// Negative while the puzzle is still locked, [`None`] if no year is configured.
+    pub days_since_unlock: Option<i64>,
+}
+
+/// The stored data every [`DayStatus`] is collected from.
+pub struct Sources {
+    pub answers: Answers,
+    pub timings: Timings,
+    pub history: History,
+    pub year: Option<u16>,
+    /// Seconds since the unix epoch.
+    pub now: i64,
+}
+
+impl DayStatus {
+    pub fn collect(day: Day, sources: &Sources, tests: Option<bool>) -> Self {
+        let input = inputs::status(None, day);
+        let answer = sources.answers.get(day);
+
+        let benchmark = sources
+            .timings
+            .data
+            .iter()
+            .find(|timing| timing.day == day)
+            .map(|timing| Benchmark {
+                total_nanos: timing.total_nanos,
+                timestamp: sources
+                    .history
+                    .runs
+                    .iter()
+                    .rev()
+                    .find(|run| run.timings.data.iter().any(|t| t.day == day))
+                    .map(|run| run.timestamp),
+            });
+
+        DayStatus {
+            day,
+            scaffolded: Path::new(&get_path_for_bin(day)).exists(),
+            input: input.plaintext || input.encrypted,
+            example: fs::metadata(format!("data/examples/{day}.txt")).is_ok_and(|m| m.len() > 0),
+            tests,
+            solved: answer.map_or(0, |answer| answer.stars()),
+            answers: answer.map_or(0, |answer| {
+                [&answer.part_1, &answer.part_2]
+                    .iter()
+                    .filter(|part| part.as_ref().is_some_and(|p| p.answer.is_some()))
+                    .count()
+            }),
+            benchmark,
+            days_since_unlock: sources.year.map(|year| {
+                (sources.now - unlock_timestamp(year, day)).div_euclid(SECONDS_PER_DAY)
+            }),
+        }
+    }
+}
+
+/// The unix timestamp at which the puzzle of `day` in December of `year` unlocks.
+pub fn unlock_timestamp(year: u16, day: Day) -> i64 {
+    days_from_civil(i64::from(year), 12, i64::from(day.into_inner())) * SECONDS_PER_DAY
+        + UNLOCK_UTC_OFFSET
+}
+
+/// Days since the unix epoch of a date in the proleptic Gregorian calendar.
+/// see: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&DayStatus> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &DayStatus) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("scaffolded".into(), JsonValue::Boolean(value.scaffolded));
+        map.insert("input".into(), JsonValue::Boolean(value.input));
+        map.insert("example".into(), JsonValue::Boolean(value.example));
+        map.insert(
+            "tests".into(),
+            value.tests.map_or(JsonValue::Null, JsonValue::Boolean),
+        );
+        map.insert("solved".into(), JsonValue::Number(value.solved as f64));
+        map.insert("answers".into(), JsonValue::Number(value.answers as f64));
+        map.insert(
+            "benchmark".into(),
+            value
+                .benchmark
+                .as_ref()
+                .map_or(JsonValue::Null, |benchmark| {
+                    let mut map: HashMap<String, JsonValue> = HashMap::new();
+                    map.insert(
+                        "total_nanos".into(),
+                        JsonValue::Number(benchmark.total_nanos),
+                    );
+                    map.insert(
+                        "timestamp".into(),
+                        benchmark
+                            .timestamp
+                            .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+                    );
+                    JsonValue::Object(map)
+                }),
+        );
+        map.insert(
+            "days_since_unlock".into(),
+            value
+                .days_since_unlock
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{days_from_civil, unlock_timestamp, DayStatus, Sources};
+    use crate::day;
+    use crate::template::answers::Answers;
+    use crate::template::history::History;
+    use crate::template::timings::{Timing, Timings};
+
+    #[test]
+    fn computes_unlock_times() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        // 2024-12-01T05:00:00Z
+        assert_eq!(unlock_timestamp(2024, day!(1)), 1_733_029_200);
+    }
+
+    #[test]
+    fn collects_status() {
+        let timings = Timings {
+            data: vec![Timing {
+                part_1: Some("1ms".into()),
+                total_nanos: 1e6,
+                ..Timing::new(day!(1))
+            }],
+        };
+        let sources = Sources {
+            answers: Answers::default()
+                .record(day!(1), 1, Some("42".into()))
+                .record(day!(1), 2, None),
+            history: History::default(),
+            timings,
+            year: Some(2024),
+            now: unlock_timestamp(2024, day!(1)) + 86_400 + 1,
+        };
+
+        let status = DayStatus::collect(day!(1), &sources, Some(true));
+        assert_eq!(status.solved, 2);
+        assert_eq!(status.answers, 1);
+        assert_eq!(status.days_since_unlock, Some(1));
+        assert_eq!(status.benchmark.unwrap().timestamp, None);
+
+        let status = DayStatus::collect(day!(3), &sources, None);
+        assert_eq!(status.days_since_unlock, Some(-1));
+        assert_eq!(status.benchmark, None);
+    }
+}
@@ -12,6 +12,40 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// Time taken by the input parser on its own, if the solution registered it.
+    pub parse: Option<String>,
+    /// Number of benchmark samples taken for each part.
+    pub samples: [Option<u64>; 2],
+    /// Number of allocations made by a single run of each part.
+    pub allocations: [Option<u64>; 2],
+    /// Total time of the run this timing replaced, used to show the change between runs.
+    pub previous_total_nanos: Option<f64>,
+    /// The solution only has a first part, see [`Day::is_single_part`].
+    pub single_part: bool,
+    /// Whether the timing was replayed from cached output instead of a new run. Not stored.
+    pub cached: bool,
+}
+
+impl Timing {
+    pub fn new(day: Day) -> Self {
+        Timing {
+            day,
+            part_1: None,
+            part_2: None,
+            total_nanos: 0_f64,
+            parse: None,
+            samples: [None; 2],
+            allocations: [None; 2],
+            previous_total_nanos: None,
+            single_part: day.is_single_part(),
+            cached: false,
+        }
+    }
+
+    /// Whether every part of the solution was benched.
+    pub fn is_complete(&self) -> bool {
+        self.part_1.is_some() && (self.part_2.is_some() || self.single_part)
+    }
 }
 
 /// Represents benchmark times for a set of days.
@@ -45,11 +79,21 @@ impl Timings {
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// Replaced timings are remembered in [`Timing::previous_total_nanos`], unless the new timing
+    /// was replayed from cache: nothing was run again, so the stored change is kept.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
         for timing in &new.data {
-            data.push(timing.clone());
+            let mut timing = timing.clone();
+            if let Some(previous) = self.data.iter().find(|t| t.day == timing.day) {
+                timing.previous_total_nanos = if timing.cached {
+                    previous.previous_total_nanos
+                } else {
+                    Some(previous.total_nanos)
+                };
+            }
+            data.push(timing);
         }
 
         for timing in &self.data {
@@ -58,7 +102,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -68,9 +112,7 @@ impl Timings {
     }
 
     pub fn is_day_complete(&self, day: Day) -> bool {
-        self.data
-            .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+        self.data.iter().any(|t| t.day == day && t.is_complete())
     }
 }
 
@@ -140,6 +182,35 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        // NOTE: the fields below were added later and are only written when known.
+        if let Some(parse) = &value.parse {
+            map.insert("parse".into(), JsonValue::String(parse.clone()));
+        }
+
+        let counts = [
+            ("samples", &value.samples),
+            ("allocations", &value.allocations),
+        ];
+        for (key, count) in counts {
+            for (index, count) in count.iter().enumerate() {
+                if let Some(count) = count {
+                    #[allow(clippy::cast_precision_loss)]
+                    map.insert(
+                        format!("{key}_{}", index + 1),
+                        JsonValue::Number(*count as f64),
+                    );
+                }
+            }
+        }
+
+        if let Some(previous) = value.previous_total_nanos {
+            map.insert("previous_total_nanos".into(), JsonValue::Number(previous));
+        }
+
+        if value.single_part {
+            map.insert("single_part".into(), JsonValue::Boolean(true));
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -173,11 +244,35 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        let parse = json.get("parse").and_then(|v| v.get::<String>()).cloned();
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let count = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+        };
+
+        let previous_total_nanos = json
+            .get("previous_total_nanos")
+            .and_then(|v| v.get::<f64>().copied());
+
+        let single_part = json
+            .get("single_part")
+            .and_then(|v| v.get::<bool>().copied())
+            .unwrap_or_else(|| day.is_single_part());
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            parse,
+            samples: [count("samples_1"), count("samples_2")],
+            allocations: [count("allocations_1"), count("allocations_2")],
+            previous_total_nanos,
+            single_part,
+            cached: false,
         })
     }
 }
@@ -198,18 +293,21 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    ..Timing::new(day!(1))
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    ..Timing::new(day!(2))
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    ..Timing::new(day!(4))
                 },
             ],
         }
@@ -289,10 +387,11 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -303,10 +402,32 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    ..Timing::new(day!(1))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
+        }
+
+        #[test]
+        fn handles_single_part_days() {
+            let timing = |day| Timing {
+                part_1: Some("1ms".into()),
+                total_nanos: 1_000_000_f64,
+                ..Timing::new(day)
+            };
+            let timings = Timings {
+                data: vec![
+                    timing(day!(25)),
+                    Timing {
+                        single_part: true,
+                        ..timing(day!(2))
+                    },
+                ],
+            };
+
+            assert_eq!(timings.is_day_complete(day!(25)), true);
+            assert_eq!(timings.is_day_complete(day!(2)), true);
         }
 
         #[test]
@@ -317,10 +438,11 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    ..Timing::new(day!(1))
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -341,6 +463,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    ..Timing::new(day!(3))
                 }],
             };
             let merged = timings.merge(&other);
@@ -361,6 +484,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    ..Timing::new(day!(2))
                 }],
             };
             let merged = timings.merge(&other);
@@ -387,5 +511,31 @@ mod tests {
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 3);
         }
+
+        #[test]
+        fn keeps_previous_total_of_cached_timings() {
+            let timings = Timings {
+                data: vec![Timing {
+                    total_nanos: 2_f64,
+                    previous_total_nanos: Some(1_f64),
+                    ..Timing::new(day!(1))
+                }],
+            };
+            let rerun = Timing {
+                total_nanos: 3_f64,
+                ..Timing::new(day!(1))
+            };
+            let cached = Timing {
+                total_nanos: 2_f64,
+                cached: true,
+                ..Timing::new(day!(1))
+            };
+
+            let merged = timings.merge(&Timings { data: vec![rerun] });
+            assert_eq!(merged.data[0].previous_total_nanos, Some(2_f64));
+
+            let merged = timings.merge(&Timings { data: vec![cached] });
+            assert_eq!(merged.data[0].previous_total_nanos, Some(1_f64));
+        }
     }
 }
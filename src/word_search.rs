@@ -0,0 +1,123 @@
+/// Word searches on the shared [`Grid`]: words reading in any direction and 2D patterns with
+/// wildcards, over any kind of cell.
+use crate::grid::{Grid, Position, ALL_DIRECTIONS};
+
+/// Where a word or pattern was found: the position of its first cell and the direction its
+/// first row reads in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub start: Position,
+    pub direction: (isize, isize),
+}
+
+/// Finds every occurrence of `word` reading in any of the eight directions. Words that read the
+/// same backwards, like single letters, are found once per direction they read in.
+pub fn find_word<T: PartialEq + Clone>(grid: &Grid<T>, word: &[T]) -> Vec<Match> {
+    let pattern = Grid::new(word.len(), 1, word.iter().cloned().map(Some).collect());
+    find_pattern(grid, &pattern, &ALL_DIRECTIONS)
+}
+
+/// Parses a pattern from text, where `wildcard` matches any cell.
+pub fn parse_pattern(text: &str, wildcard: char) -> Grid<Option<char>> {
+    Grid::parse(text, |_, c| (c != wildcard).then_some(c))
+}
+
+/// Finds every placement of `pattern` where all of its cells other than [`None`] match the grid.
+/// The pattern's rows read in each of `directions`, with successive rows a quarter turn clockwise
+/// from them, so [`ORTHOGONAL`](crate::grid::ORTHOGONAL) tries all four rotations and `&[(0, 1)]`
+/// only the pattern as written.
+pub fn find_pattern<T: PartialEq>(
+    grid: &Grid<T>,
+    pattern: &Grid<Option<T>>,
+    directions: &[(isize, isize)],
+) -> Vec<Match> {
+    grid.positions()
+        .flat_map(|start| {
+            directions
+                .iter()
+                .map(move |&direction| Match { start, direction })
+        })
+        .filter(|found| matches_at(grid, pattern, found))
+        .collect()
+}
+
+fn matches_at<T: PartialEq>(grid: &Grid<T>, pattern: &Grid<Option<T>>, found: &Match) -> bool {
+    let (d_row, d_col) = found.direction;
+    let (down_row, down_col) = (d_col, -d_row);
+
+    pattern.iter().all(|((row, col), expected)| {
+        let Some(expected) = expected else {
+            return true;
+        };
+        let (row, col) = (row as isize, col as isize);
+        let offset = (row * down_row + col * d_row, row * down_col + col * d_col);
+
+        grid.offset(found.start, offset)
+            .and_then(|position| grid.get(position))
+            == Some(expected)
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find_pattern, find_word, parse_pattern, Match};
+    use crate::grid::{Grid, ORTHOGONAL};
+
+    #[test]
+    fn finds_words_in_all_directions() {
+        let grid = Grid::parse("CAT\nA..\nTAC", |_, c| c);
+        let mut found = find_word(&grid, &['C', 'A', 'T']);
+        found.sort_by_key(|found| (found.start, found.direction));
+
+        assert_eq!(
+            found,
+            vec![
+                Match {
+                    start: (0, 0),
+                    direction: (0, 1)
+                },
+                Match {
+                    start: (0, 0),
+                    direction: (1, 0)
+                },
+                Match {
+                    start: (2, 2),
+                    direction: (0, -1)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_words_over_any_alphabet() {
+        let grid = Grid::new(3, 3, vec![1, 0, 0, 0, 2, 0, 0, 0, 3]);
+        assert_eq!(find_word(&grid, &[1, 2, 3]).len(), 1);
+        assert_eq!(find_word(&grid, &[3, 2, 1]).len(), 1);
+        assert_eq!(find_word(&grid, &[1, 2, 4]).len(), 0);
+    }
+
+    #[test]
+    fn matches_patterns_with_wildcards() {
+        let grid = Grid::parse("M.S\n.A.\nM.S\n", |_, c| c);
+        let pattern = parse_pattern("M?S\n?A?\nM?S", '?');
+
+        assert_eq!(
+            find_pattern(&grid, &pattern, &[(0, 1)]),
+            vec![Match {
+                start: (0, 0),
+                direction: (0, 1)
+            }]
+        );
+
+        let rotated = parse_pattern("M?M\n?A?\nS?S", '?');
+        assert_eq!(
+            find_pattern(&grid, &rotated, &ORTHOGONAL),
+            vec![Match {
+                start: (2, 0),
+                direction: (-1, 0)
+            }]
+        );
+    }
+}